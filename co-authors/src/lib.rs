@@ -1,4 +1,7 @@
-use std::convert::{identity, TryFrom};
+use std::{
+    borrow::Cow,
+    convert::{identity, TryFrom},
+};
 
 use nom::{
     bytes::complete::{is_not, tag, tag_no_case, take_until},
@@ -10,6 +13,16 @@ use nom::{
     Err, IResult,
 };
 
+pub use crate::trailer::{
+    append_trailers, last_paragraph, trailers, trailers_in, trailers_with, Trailer, TrailerOptions,
+    Trailers,
+};
+
+mod trailer;
+
+/// The key of the trailer that names a co-author, compared case insensitively.
+pub const CO_AUTHORED_BY: &str = "Co-authored-by";
+
 /// A co-author as parsed from a [Co-Authored-By trailer].
 ///
 /// # Example
@@ -19,14 +32,14 @@ use nom::{
 /// # use std::convert::TryFrom;
 /// let trailer = "Co-Authored-By: Alice <alice@wonderland.org>";
 /// let co_author = CoAuthor::try_from(trailer);
-/// assert_eq!(co_author, Ok(CoAuthor { name: "Alice", mail: Some("alice@wonderland.org") }));
+/// assert_eq!(co_author, Ok(CoAuthor::new("Alice", Some("alice@wonderland.org"))));
 /// ```
 ///
 /// [Co-Authored-By trailer]: https://github.blog/2018-01-29-commit-together-with-co-authors/
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CoAuthor<'a> {
-    pub name: &'a str,
-    pub mail: Option<&'a str>,
+    pub name: Cow<'a, str>,
+    pub mail: Option<Cow<'a, str>>,
 }
 
 impl<'a> TryFrom<&'a str> for CoAuthor<'a> {
@@ -34,20 +47,137 @@ impl<'a> TryFrom<&'a str> for CoAuthor<'a> {
 
     fn try_from(line: &'a str) -> Result<Self, Self::Error> {
         match co_author(line) {
-            Ok((_, (name, mail))) => Ok(CoAuthor { name, mail }),
-            Err(e) => match e {
-                Err::Incomplete(_) => Err(CoAuthorError::MissingTrailerKey),
-                Err::Error(e) | Err::Failure(e) => match e.code {
-                    ErrorKind::Tag => Err(CoAuthorError::MissingTrailerKey),
-                    ErrorKind::TakeUntil => Err(CoAuthorError::MissingMail),
-                    ErrorKind::Verify => Err(CoAuthorError::MissingName),
-                    otherwise => unreachable!("Unexpected error kind: {:?}", otherwise),
-                },
-            },
+            Ok((_, (name, mail))) => Ok(CoAuthor::new(name, mail)),
+            Err(e) => Err(co_author_error(e)),
+        }
+    }
+}
+
+/// Reads the co-author from a [Trailer] with the `Co-authored-by` key.
+///
+/// # Example
+///
+/// ```rust
+/// # use co_authors::{trailers, CoAuthor};
+/// # use std::convert::TryFrom;
+/// let message = "Fix the flux capacitor\n\nCo-authored-by: Alice <alice@wonderland.org>\n";
+/// let co_authors = trailers(message)
+///     .filter_map(|trailer| CoAuthor::try_from(&trailer).ok())
+///     .collect::<Vec<_>>();
+/// assert_eq!(co_authors, vec![CoAuthor::new("Alice", Some("alice@wonderland.org"))]);
+/// ```
+impl<'a> TryFrom<&Trailer<'a>> for CoAuthor<'a> {
+    type Error = CoAuthorError;
+
+    fn try_from(trailer: &Trailer<'a>) -> Result<Self, Self::Error> {
+        if !trailer.key.eq_ignore_ascii_case(CO_AUTHORED_BY) {
+            return Err(CoAuthorError::MissingTrailerKey);
+        }
+        CoAuthor::from_trailer(trailer)
+    }
+}
+
+impl<'a> CoAuthor<'a> {
+    /// A co-author that borrows its name and mail.
+    pub fn new(name: &'a str, mail: Option<&'a str>) -> Self {
+        CoAuthor {
+            name: Cow::Borrowed(name),
+            mail: mail.map(Cow::Borrowed),
+        }
+    }
+
+    /// Parses the `Name <mail>` value of a trailer, regardless of its key,
+    /// e.g. for `Reviewed-by` or `Paired-with` trailers.
+    pub fn from_value(value: &'a str) -> Result<Self, CoAuthorError> {
+        match name_and_mail(value) {
+            Ok((_, (name, mail))) => Ok(CoAuthor::new(name, mail)),
+            Err(e) => Err(co_author_error(e)),
+        }
+    }
+
+    /// Parses the [unfolded value](Trailer::unfolded_value) of a trailer, regardless of its key,
+    /// so that a name spanning continuation lines is joined by a single space.
+    pub fn from_trailer(trailer: &Trailer<'a>) -> Result<Self, CoAuthorError> {
+        match trailer.unfolded_value() {
+            Cow::Borrowed(value) => CoAuthor::from_value(value),
+            Cow::Owned(value) => CoAuthor::from_value(&value).map(CoAuthor::into_owned),
+        }
+    }
+
+    /// Copies the name and mail, so that the co-author outlives the message it was parsed from.
+    pub fn into_owned(self) -> CoAuthor<'static> {
+        CoAuthor {
+            name: Cow::Owned(self.name.into_owned()),
+            mail: self.mail.map(|mail| Cow::Owned(mail.into_owned())),
+        }
+    }
+}
+
+/// Where [co_authors] and [trailers_in] look for trailers in a commit message.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// Only trailers in the trailer block at the end of the message, see [trailers].
+    #[default]
+    Strict,
+    /// Any line that looks like a trailer, even if it is part of the body.
+    Lenient,
+}
+
+/// Iterates over the co-authors of a commit message, see [co_authors].
+#[derive(Clone, Debug)]
+pub struct CoAuthors<'a>(CoAuthorLines<'a>);
+
+#[derive(Clone, Debug)]
+enum CoAuthorLines<'a> {
+    Trailers(Trailers<'a>),
+    Lines(std::str::Lines<'a>),
+}
+
+impl<'a> Iterator for CoAuthors<'a> {
+    type Item = CoAuthor<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            CoAuthorLines::Trailers(trailers) => {
+                trailers.find_map(|trailer| CoAuthor::try_from(&trailer).ok())
+            }
+            CoAuthorLines::Lines(lines) => lines.find_map(|line| CoAuthor::try_from(line).ok()),
         }
     }
 }
 
+/// Returns all co-authors of a full commit message.
+///
+/// In [Mode::Strict], a `Co-authored-by` line that is quoted in the body,
+/// e.g. in the message of a revert, is not a co-author.
+///
+/// # Example
+///
+/// ```rust
+/// # use co_authors::{co_authors, CoAuthor, Mode};
+/// let message = "Revert \"Add feature\"\n\n> Co-authored-by: Alice <alice@wonderland.org>\n\nCo-authored-by: Bob <bob@wonderland.org>\n";
+/// let co_authors = co_authors(message, Mode::Strict).collect::<Vec<_>>();
+/// assert_eq!(co_authors, vec![CoAuthor::new("Bob", Some("bob@wonderland.org"))]);
+/// ```
+pub fn co_authors(message: &str, mode: Mode) -> CoAuthors<'_> {
+    match mode {
+        Mode::Strict => CoAuthors(CoAuthorLines::Trailers(trailers(message))),
+        Mode::Lenient => CoAuthors(CoAuthorLines::Lines(message.lines())),
+    }
+}
+
+fn co_author_error(e: Err<nom::error::Error<&str>>) -> CoAuthorError {
+    match e {
+        Err::Incomplete(_) => CoAuthorError::MissingTrailerKey,
+        Err::Error(e) | Err::Failure(e) => match e.code {
+            ErrorKind::Tag => CoAuthorError::MissingTrailerKey,
+            ErrorKind::TakeUntil => CoAuthorError::MissingMail,
+            ErrorKind::Verify => CoAuthorError::MissingName,
+            otherwise => unreachable!("Unexpected error kind: {:?}", otherwise),
+        },
+    }
+}
+
 /// Possible errors when parsing the [CoAuthor].
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum CoAuthorError {
@@ -74,9 +204,9 @@ impl std::fmt::Display for CoAuthorError {
 impl std::error::Error for CoAuthorError {}
 
 #[deprecated(since = "0.1.0", note = "Use `CoAuthor::try_from` instead")]
-pub fn get_co_author(line: &str) -> Option<CoAuthor> {
+pub fn get_co_author(line: &str) -> Option<CoAuthor<'_>> {
     let (_, (name, mail)) = co_author(line).ok()?;
-    Some(CoAuthor { name, mail })
+    Some(CoAuthor::new(name, mail))
 }
 
 fn co_author(input: &str) -> IResult<&str, (&str, Option<&str>)> {
@@ -85,14 +215,24 @@ fn co_author(input: &str) -> IResult<&str, (&str, Option<&str>)> {
     Ok((input, (name, email)))
 }
 
+fn name_and_mail(input: &str) -> IResult<&str, (&str, Option<&str>)> {
+    let (input, name) = name(input)?;
+    let (input, email) = co_author_mail(input)?;
+    Ok((input, (name, email)))
+}
+
 fn co_author_name(input: &str) -> IResult<&str, &str> {
-    let co_author_name = take_until("<");
-    let co_author_name = map(co_author_name, str::trim);
-    let co_author_name = verify(co_author_name, |s: &str| !s.is_empty());
-    let co_author_name = preceded(co_authored_by, co_author_name);
+    let co_author_name = preceded(co_authored_by, name);
     identity(co_author_name)(input)
 }
 
+fn name(input: &str) -> IResult<&str, &str> {
+    let name = take_until("<");
+    let name = map(name, str::trim);
+    let name = verify(name, |s: &str| !s.is_empty());
+    identity(name)(input)
+}
+
 fn co_authored_by(input: &str) -> IResult<&str, Vec<()>> {
     let co_authored_by = tag_no_case("co-authored-by:");
     let co_authored_by = map(co_authored_by, |_| ());
@@ -150,21 +290,21 @@ mod tests {
         assert_eq!(result, None)
     }
 
-    #[test_case("co-authored-by: Alice <alice@wonderland.org>" => Some("Alice"); "alice")]
-    #[test_case("co-authored-by: Alice Keys <alice@wonderland.org>" => Some("Alice Keys"); "alice keys")]
-    #[test_case("Co-Authored-By:Alice<alice@wonderland.org>" => Some("Alice"); "no space alice")]
+    #[test_case("co-authored-by: Alice <alice@wonderland.org>" => Some("Alice".into()); "alice")]
+    #[test_case("co-authored-by: Alice Keys <alice@wonderland.org>" => Some("Alice Keys".into()); "alice keys")]
+    #[test_case("Co-Authored-By:Alice<alice@wonderland.org>" => Some("Alice".into()); "no space alice")]
     #[test_case("Some other content" => None; "none")]
-    fn test_get_co_author_name(input: &str) -> Option<&str> {
+    fn test_get_co_author_name(input: &str) -> Option<Cow<'_, str>> {
         CoAuthor::try_from(input)
             .ok()
             .map(|co_author| co_author.name)
     }
 
-    #[test_case("co-authored-by: Alice <alice@wonderland.org>" => Some("alice@wonderland.org"); "alice")]
-    #[test_case("co-authored-by: Alice Keys <alice@wonderland.org>" => Some("alice@wonderland.org"); "alice keys")]
+    #[test_case("co-authored-by: Alice <alice@wonderland.org>" => Some("alice@wonderland.org".into()); "alice")]
+    #[test_case("co-authored-by: Alice Keys <alice@wonderland.org>" => Some("alice@wonderland.org".into()); "alice keys")]
     #[test_case("co-authored-by: <alice@wonderland.org>" => None; "missing name")]
     #[test_case("Some other content" => None; "none")]
-    fn test_get_co_author_mail(input: &str) -> Option<&str> {
+    fn test_get_co_author_mail(input: &str) -> Option<Cow<'_, str>> {
         CoAuthor::try_from(input)
             .ok()
             .and_then(|co_author| co_author.mail)
@@ -191,4 +331,29 @@ mod tests {
         let err = CoAuthor::try_from(input).unwrap_err();
         assert_eq!(err, CoAuthorError::MissingMail)
     }
+
+    #[test_case("co-authored-by", "Alice <alice@wonderland.org>" => Ok(CoAuthor::new("Alice", Some("alice@wonderland.org"))); "alice")]
+    #[test_case("Co-Authored-By", "Alice\n  Keys <alice@wonderland.org>" => Ok(CoAuthor::new("Alice Keys", Some("alice@wonderland.org"))); "continuation")]
+    #[test_case("Reviewed-by", "Alice <alice@wonderland.org>" => Err(CoAuthorError::MissingTrailerKey); "other key")]
+    #[test_case("Co-authored-by", "<alice@wonderland.org>" => Err(CoAuthorError::MissingName); "missing name")]
+    #[test_case("Co-authored-by", "Alice" => Err(CoAuthorError::MissingMail); "missing mail")]
+    fn test_co_author_from_trailer<'a>(
+        key: &'a str,
+        value: &'a str,
+    ) -> Result<CoAuthor<'a>, CoAuthorError> {
+        CoAuthor::try_from(&Trailer { key, value })
+    }
+
+    const QUOTED: &str = "Revert \"Add feature\"\n\nThis reverts the commit\n\n    Co-authored-by: Alice <alice@wonderland.org>\n\nCo-authored-by: Bob <bob@wonderland.org>\n";
+    const SUBJECT_ONLY: &str = "Co-authored-by: Alice <alice@wonderland.org>";
+
+    #[test_case(QUOTED, Mode::Strict => vec!["Bob"]; "strict quoted")]
+    #[test_case(QUOTED, Mode::Lenient => vec!["Alice", "Bob"]; "lenient quoted")]
+    #[test_case(SUBJECT_ONLY, Mode::Strict => Vec::<&str>::new(); "strict subject only")]
+    #[test_case(SUBJECT_ONLY, Mode::Lenient => vec!["Alice"]; "lenient subject only")]
+    fn test_co_authors(message: &str, mode: Mode) -> Vec<Cow<'_, str>> {
+        co_authors(message, mode)
+            .map(|co_author| co_author.name)
+            .collect()
+    }
 }
@@ -0,0 +1,463 @@
+use std::{borrow::Cow, convert::identity};
+
+use crate::Mode;
+use nom::{
+    bytes::complete::take_while1,
+    character::complete::{one_of, space0},
+    sequence::terminated,
+    IResult,
+};
+
+/// Prefixes of lines that git itself adds to the trailer block.
+/// A paragraph with one of these is a trailer block even if it contains other lines.
+const GIT_GENERATED_PREFIXES: [&str; 2] = ["Signed-off-by: ", "(cherry picked from commit "];
+
+/// The line that `git commit --verbose` uses to cut off the diff.
+const SCISSORS: &str = "------------------------ >8 ------------------------";
+
+/// A single `key: value` trailer from the end of a commit message.
+///
+/// The value is the raw text after the separator. It spans multiple lines
+/// if the trailer has continuation lines, see [Trailer::unfolded_value].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Trailer<'a> {
+    pub key: &'a str,
+    pub value: &'a str,
+}
+
+impl<'a> Trailer<'a> {
+    /// The value with continuation lines joined by a single space.
+    pub fn unfolded_value(&self) -> Cow<'a, str> {
+        if !self.value.contains('\n') {
+            return Cow::Borrowed(self.value);
+        }
+        let mut value = String::with_capacity(self.value.len());
+        for line in self.value.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if !value.is_empty() {
+                value.push(' ');
+            }
+            value.push_str(line);
+        }
+        Cow::Owned(value)
+    }
+}
+
+/// Controls how the trailer block is recognized, like the respective `git config` settings.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TrailerOptions<'a> {
+    /// Characters that separate the key from the value, see `trailer.separators`.
+    pub separators: &'a str,
+    /// Lines starting with this character are ignored, see `core.commentChar`.
+    pub comment_char: Option<char>,
+}
+
+impl Default for TrailerOptions<'_> {
+    fn default() -> Self {
+        Self {
+            separators: ":",
+            comment_char: Some('#'),
+        }
+    }
+}
+
+/// Iterates over the trailers at the end of a commit message, see [trailers].
+#[derive(Clone, Debug)]
+pub struct Trailers<'a>(std::vec::IntoIter<Trailer<'a>>);
+
+impl<'a> Iterator for Trailers<'a> {
+    type Item = Trailer<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+/// Returns the trailers of a full commit message with the default [TrailerOptions].
+///
+/// The rules follow `git interpret-trailers`:
+///
+/// - the trailers are in the last paragraph of the message, which cannot be the subject
+/// - every line of that paragraph is a trailer or a continuation line starting with whitespace,
+///   or at least a quarter of the lines are trailers and one of them was generated by git,
+///   e.g. `Signed-off-by`
+/// - comment lines and everything after the scissors line of `git commit --verbose` are ignored
+///
+/// # Example
+///
+/// ```rust
+/// # use co_authors::{trailers, Trailer};
+/// let message = "Fix the flux capacitor\n\nCo-authored-by: Alice <alice@wonderland.org>\n";
+/// let trailers = trailers(message).collect::<Vec<_>>();
+/// assert_eq!(trailers, vec![Trailer { key: "Co-authored-by", value: "Alice <alice@wonderland.org>" }]);
+/// ```
+pub fn trailers(message: &str) -> Trailers<'_> {
+    trailers_with(message, TrailerOptions::default())
+}
+
+/// Returns the trailers of a full commit message, see [trailers].
+pub fn trailers_with<'a>(message: &'a str, options: TrailerOptions<'_>) -> Trailers<'a> {
+    let lines = lines(message, options);
+    let block = &lines[trailer_block_start(&lines, options)..];
+
+    let mut trailers = Vec::new();
+    let mut current: Option<(&str, usize, usize)> = None;
+    for &(offset, line) in block {
+        if let Some((key, separator_end)) = split_trailer(line, options) {
+            trailers.extend(current.take().map(|t| trailer(message, t)));
+            let value_start = offset + separator_end;
+            current = Some((key, value_start, offset + line.len()));
+        } else if starts_with_whitespace(line) {
+            if let Some((_, _, end)) = current.as_mut() {
+                *end = offset + line.len();
+            }
+        } else {
+            trailers.extend(current.take().map(|t| trailer(message, t)));
+        }
+    }
+    trailers.extend(current.map(|t| trailer(message, t)));
+
+    Trailers(trailers.into_iter())
+}
+
+/// Returns the trailers of a full commit message, see [Mode].
+///
+/// In [Mode::Lenient], every line that looks like a trailer is returned, wherever it is in the message,
+/// except for comments and the diff of `git commit --verbose`.
+pub fn trailers_in(message: &str, mode: Mode) -> Trailers<'_> {
+    match mode {
+        Mode::Strict => trailers(message),
+        Mode::Lenient => {
+            let options = TrailerOptions::default();
+            let trailers = lines(message, options)
+                .into_iter()
+                .map(|(_, line)| line.trim_start())
+                .filter_map(|line| {
+                    let (key, separator_end) = split_trailer(line, options)?;
+                    let value = line[separator_end..].trim();
+                    Some(Trailer { key, value })
+                })
+                .collect::<Vec<_>>();
+            Trailers(trailers.into_iter())
+        }
+    }
+}
+
+/// Returns the lines of the last paragraph of a full commit message, which is where git looks
+/// for the trailer block, see [trailers].
+///
+/// Unlike [trailers], the lines are returned even if they are not a valid trailer block,
+/// e.g. to find trailers that are malformed. The subject is never part of the last paragraph.
+pub fn last_paragraph(message: &str) -> Vec<&str> {
+    let lines = lines(message, TrailerOptions::default());
+    let end_of_title = match lines.iter().position(|(_, line)| is_blank(line)) {
+        Some(blank) => blank,
+        None => return Vec::new(),
+    };
+
+    let mut paragraph = lines[end_of_title..]
+        .iter()
+        .rev()
+        .map(|&(_, line)| line)
+        .skip_while(|line| is_blank(line))
+        .take_while(|line| !is_blank(line))
+        .collect::<Vec<_>>();
+    paragraph.reverse();
+    paragraph
+}
+
+/// Adds the trailers to the trailer block of the message, like `git interpret-trailers --trailer`.
+///
+/// A new trailer block is started if the message does not end with one.
+/// Trailers are inserted before any trailing comments, so that this works with the file
+/// that is passed to the `prepare-commit-msg` hook. Trailers that are already present are skipped.
+///
+/// # Example
+///
+/// ```rust
+/// # use co_authors::{append_trailers, Trailer};
+/// let message = append_trailers(
+///     "Fix the flux capacitor\n",
+///     &[Trailer { key: "Co-authored-by", value: "Alice <alice@wonderland.org>" }],
+/// );
+/// assert_eq!(message, "Fix the flux capacitor\n\nCo-authored-by: Alice <alice@wonderland.org>\n");
+/// ```
+pub fn append_trailers(message: &str, new_trailers: &[Trailer<'_>]) -> String {
+    let existing = trailers(message).collect::<Vec<_>>();
+    let new_trailers = new_trailers
+        .iter()
+        .filter(|new| {
+            !existing.iter().any(|trailer| {
+                trailer.key.eq_ignore_ascii_case(new.key)
+                    && trailer.unfolded_value() == new.unfolded_value()
+            })
+        })
+        .collect::<Vec<_>>();
+    if new_trailers.is_empty() {
+        return message.to_string();
+    }
+
+    let options = TrailerOptions::default();
+    let lines = lines(message, options);
+    let (insert_at, separator) = match lines.iter().rposition(|(_, line)| !is_blank(line)) {
+        Some(last) => {
+            let (offset, line) = lines[last];
+            let end = message[offset + line.len()..]
+                .find('\n')
+                .map_or(message.len(), |newline| offset + line.len() + newline + 1);
+            let has_trailer_block = trailer_block_start(&lines, options) < lines.len();
+            let newline = if message[..end].ends_with('\n') {
+                ""
+            } else {
+                "\n"
+            };
+            (
+                end,
+                if has_trailer_block {
+                    newline.to_string()
+                } else {
+                    format!("{}\n", newline)
+                },
+            )
+        }
+        // leave room for the subject
+        None => (0, String::from("\n\n")),
+    };
+
+    let mut result = String::with_capacity(message.len() + 64 * new_trailers.len());
+    result.push_str(&message[..insert_at]);
+    result.push_str(&separator);
+    for trailer in new_trailers {
+        result.push_str(trailer.key);
+        result.push_str(": ");
+        result.push_str(trailer.value);
+        result.push('\n');
+    }
+    result.push_str(&message[insert_at..]);
+    result
+}
+
+fn trailer<'a>(message: &'a str, (key, start, end): (&'a str, usize, usize)) -> Trailer<'a> {
+    Trailer {
+        key,
+        value: message[start..end].trim(),
+    }
+}
+
+/// Splits the message into lines with their offset, without comments and the diff of a verbose commit.
+fn lines<'a>(message: &'a str, options: TrailerOptions<'_>) -> Vec<(usize, &'a str)> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in message.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let line = line.trim_end_matches(&['\n', '\r'][..]);
+        if let Some(comment) = options.comment_char.filter(|&c| line.starts_with(c)) {
+            if line[comment.len_utf8()..].trim() == SCISSORS {
+                break;
+            }
+            continue;
+        }
+        lines.push((start, line));
+    }
+    lines
+}
+
+/// Returns the index of the first line of the trailer block or the number of lines if there is none.
+fn trailer_block_start(lines: &[(usize, &str)], options: TrailerOptions<'_>) -> usize {
+    // the subject is everything up to the first blank line and never contains trailers
+    let end_of_title = match lines.iter().position(|(_, line)| is_blank(line)) {
+        Some(blank) => blank,
+        None => return lines.len(),
+    };
+
+    let mut only_spaces = true;
+    let mut recognized_prefix = false;
+    let mut trailer_lines = 0;
+    let mut non_trailer_lines = 0;
+    let mut possible_continuation_lines = 0;
+
+    for (index, (_, line)) in lines.iter().enumerate().skip(end_of_title).rev() {
+        if is_blank(line) {
+            if only_spaces {
+                continue;
+            }
+            non_trailer_lines += possible_continuation_lines;
+            let is_block = (recognized_prefix && trailer_lines * 3 >= non_trailer_lines)
+                || (trailer_lines > 0 && non_trailer_lines == 0);
+            return if is_block { index + 1 } else { lines.len() };
+        }
+        only_spaces = false;
+
+        if GIT_GENERATED_PREFIXES
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        {
+            trailer_lines += 1;
+            possible_continuation_lines = 0;
+            recognized_prefix = true;
+        } else if split_trailer(line, options).is_some() {
+            trailer_lines += 1;
+            possible_continuation_lines = 0;
+        } else if starts_with_whitespace(line) {
+            possible_continuation_lines += 1;
+        } else {
+            non_trailer_lines += 1 + possible_continuation_lines;
+            possible_continuation_lines = 0;
+        }
+    }
+
+    lines.len()
+}
+
+/// Returns the key of a trailer line and the offset right after the separator.
+fn split_trailer<'a>(line: &'a str, options: TrailerOptions<'_>) -> Option<(&'a str, usize)> {
+    let (rest, key) = trailer_key(line, options.separators).ok()?;
+    Some((key, line.len() - rest.len()))
+}
+
+fn trailer_key<'a>(input: &'a str, separators: &str) -> IResult<&'a str, &'a str> {
+    let key = take_while1(|c: char| c.is_alphanumeric() || c == '-');
+    let separator = terminated(space0, one_of(separators));
+    let trailer_key = terminated(key, separator);
+    identity(trailer_key)(input)
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+fn starts_with_whitespace(line: &str) -> bool {
+    line.starts_with(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    fn keys(message: &str) -> Vec<&str> {
+        trailers(message).map(|trailer| trailer.key).collect()
+    }
+
+    #[test_case("Co-authored-by: Alice", "Co-authored-by"; "simple")]
+    #[test_case("Reviewed-by : Alice", "Reviewed-by"; "space before separator")]
+    #[test_case("Fixes:#42", "Fixes"; "no space after separator")]
+    fn test_trailer_key(input: &str, expected: &str) {
+        let (_, key) = trailer_key(input, ":").unwrap();
+        assert_eq!(key, expected)
+    }
+
+    #[test_case(""; "empty")]
+    #[test_case(": Alice"; "missing key")]
+    #[test_case(" Co-authored-by: Alice"; "leading space")]
+    #[test_case("Co authored by: Alice"; "key with spaces")]
+    #[test_case("Co-authored-by Alice"; "missing separator")]
+    fn test_missing_trailer_key(input: &str) {
+        assert!(trailer_key(input, ":").is_err())
+    }
+
+    #[test_case("Subject\n\nCo-authored-by: Alice\nReviewed-by: Bob\n" => vec!["Co-authored-by", "Reviewed-by"]; "trailers")]
+    #[test_case("Subject\n\nBody\n\nCo-authored-by: Alice\n\n\n" => vec!["Co-authored-by"]; "trailing blank lines")]
+    #[test_case("Subject\r\n\r\nCo-authored-by: Alice\r\n" => vec!["Co-authored-by"]; "crlf")]
+    #[test_case("Co-authored-by: Alice\n" => Vec::<&str>::new(); "subject only")]
+    #[test_case("Subject\nCo-authored-by: Alice\n" => Vec::<&str>::new(); "part of the subject")]
+    #[test_case("Subject\n\nCo-authored-by: Alice\n\nSome more text\n" => Vec::<&str>::new(); "not the last paragraph")]
+    #[test_case("Subject\n\nCo-authored-by: Alice\nnot a trailer\n" => Vec::<&str>::new(); "mixed paragraph")]
+    #[test_case("Subject\n\nCo-authored-by: Alice\nnot a trailer\nSigned-off-by: Bob\n" => vec!["Co-authored-by", "Signed-off-by"]; "mixed paragraph with git trailer")]
+    #[test_case("Subject\n\na\nb\nc\nd\nSigned-off-by: Bob\n" => Vec::<&str>::new(); "too few trailers")]
+    #[test_case("Subject\n\nCo-authored-by: Alice\n# Please enter the commit message\n" => vec!["Co-authored-by"]; "comments")]
+    #[test_case("Subject\n\nCo-authored-by: Alice\n# ------------------------ >8 ------------------------\ndiff --git a/x b/x\n" => vec!["Co-authored-by"]; "scissors")]
+    fn test_trailers(message: &str) -> Vec<&str> {
+        keys(message)
+    }
+
+    #[test_case("Subject\n\nBody\n\nCo-authored-by Alice\nFixes: #42\n\n" => vec!["Co-authored-by Alice", "Fixes: #42"]; "last paragraph")]
+    #[test_case("Subject\nCo-authored-by: Alice\n" => Vec::<&str>::new(); "subject only")]
+    #[test_case("Subject\n\nCo-authored-by: Alice\n# ------------------------ >8 ------------------------\n\ndiff --git a/x b/x\n" => vec!["Co-authored-by: Alice"]; "scissors")]
+    fn test_last_paragraph(message: &str) -> Vec<&str> {
+        last_paragraph(message)
+    }
+
+    #[test]
+    fn test_quoted_trailer_in_body() {
+        let message = "Revert \"Add feature\"\n\nThis reverts the commit\n\n    Co-authored-by: Alice <alice@wonderland.org>\n\nwhich broke the build.\n\nCo-authored-by: Bob <bob@wonderland.org>\n";
+        assert_eq!(
+            trailers(message).collect::<Vec<_>>(),
+            vec![Trailer {
+                key: "Co-authored-by",
+                value: "Bob <bob@wonderland.org>"
+            }]
+        );
+    }
+
+    #[test]
+    fn test_continuation_lines() {
+        let message = "Subject\n\nNote: this is a\n  very long note\nCo-authored-by: Alice\n";
+        let trailers = trailers(message).collect::<Vec<_>>();
+        assert_eq!(trailers.len(), 2);
+        assert_eq!(trailers[0].value, "this is a\n  very long note");
+        assert_eq!(trailers[0].unfolded_value(), "this is a very long note");
+        assert_eq!(trailers[1].unfolded_value(), Cow::Borrowed("Alice"));
+    }
+
+    #[test]
+    fn test_separators() {
+        let message = "Subject\n\nCo-authored-by = Alice\nFixes #42\n";
+        let options = TrailerOptions {
+            separators: "=#",
+            ..TrailerOptions::default()
+        };
+        let trailers = trailers_with(message, options).collect::<Vec<_>>();
+        assert_eq!(
+            trailers,
+            vec![
+                Trailer {
+                    key: "Co-authored-by",
+                    value: "Alice"
+                },
+                Trailer {
+                    key: "Fixes",
+                    value: "42"
+                }
+            ]
+        );
+        assert_eq!(keys(message), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_lenient() {
+        let message = "Subject\n\n    Co-authored-by: Alice\n\nSome text\n";
+        assert_eq!(keys(message), Vec::<&str>::new());
+        assert_eq!(
+            trailers_in(message, Mode::Lenient).collect::<Vec<_>>(),
+            vec![Trailer {
+                key: "Co-authored-by",
+                value: "Alice"
+            }]
+        );
+
+        let message = "Subject\n\nCo-authored-by: Alice\n# Co-authored-by: Bob\n# ------------------------ >8 ------------------------\n+Co-authored-by: Charlie\n";
+        assert_eq!(
+            trailers_in(message, Mode::Lenient)
+                .map(|trailer| trailer.value)
+                .collect::<Vec<_>>(),
+            vec!["Alice"]
+        );
+    }
+
+    const ALICE: Trailer<'static> = Trailer {
+        key: "Co-authored-by",
+        value: "Alice <alice@wonderland.org>",
+    };
+
+    #[test_case("Subject" => "Subject\n\nCo-authored-by: Alice <alice@wonderland.org>\n"; "no newline")]
+    #[test_case("Subject\n\nBody\n" => "Subject\n\nBody\n\nCo-authored-by: Alice <alice@wonderland.org>\n"; "body")]
+    #[test_case("Subject\n\nReviewed-by: Bob\n" => "Subject\n\nReviewed-by: Bob\nCo-authored-by: Alice <alice@wonderland.org>\n"; "existing trailer block")]
+    #[test_case("Subject\n\nco-authored-by: Alice <alice@wonderland.org>\n" => "Subject\n\nco-authored-by: Alice <alice@wonderland.org>\n"; "already present")]
+    #[test_case("\n# Please enter the commit message\n" => "\n\nCo-authored-by: Alice <alice@wonderland.org>\n\n# Please enter the commit message\n"; "empty message with comments")]
+    #[test_case("Subject\n\n# Please enter the commit message\n" => "Subject\n\nCo-authored-by: Alice <alice@wonderland.org>\n\n# Please enter the commit message\n"; "subject with comments")]
+    fn test_append_trailers(message: &str) -> String {
+        append_trailers(message, &[ALICE])
+    }
+}
@@ -0,0 +1,590 @@
+use crate::StringCache;
+use fxhash::FxHashMap;
+use itertools::Itertools;
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::{AddAssign, Index},
+};
+
+/// How two authors worked together.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Relationship {
+    /// The authors paired on a commit, i.e. one was the co-author of the other.
+    #[default]
+    Pairing,
+    /// One author reviewed the commit of the other.
+    Review,
+}
+
+/// How the commits of a pair are weighted.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Weight {
+    /// Every commit counts the same
+    #[default]
+    Commits,
+    /// The number of inserted and deleted lines
+    Lines,
+    /// The number of changed files
+    Files,
+}
+
+/// The size of the changes of a commit compared to its first parent, see [crate::ScanOptions::diff_stats].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct DiffStats {
+    pub insertions: u64,
+    pub deletions: u64,
+    pub files: u64,
+}
+
+impl DiffStats {
+    /// Inserted and deleted lines.
+    pub fn lines(&self) -> u64 {
+        self.insertions + self.deletions
+    }
+}
+
+impl AddAssign for DiffStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.insertions += rhs.insertions;
+        self.deletions += rhs.deletions;
+        self.files += rhs.files;
+    }
+}
+
+/// How often each author paired with any other author, by their id in the [StringCache].
+#[derive(Debug, Default)]
+pub struct AuthorCounts(FxHashMap<usize, PairingCounts>);
+
+impl AuthorCounts {
+    /// Records a commit by `driver` that was co-authored by all `navigators`.
+    /// A commit without any navigator other than the driver is a solo commit.
+    pub fn add_commit(&mut self, driver: usize, navigators: &[usize], changes: DiffStats) {
+        let navigators = distinct_navigators(driver, navigators);
+        let group_size = navigators.len() + 1;
+        self.author(driver).add_group(group_size);
+        if navigators.is_empty() {
+            self.author(driver).solo += 1;
+            return;
+        }
+
+        self.author(driver).paired += 1;
+        for navigator in navigators {
+            let counts = self.author(navigator);
+            counts.paired += 1;
+            counts.add_group(group_size);
+            self.add_pair(driver, navigator, changes);
+        }
+    }
+
+    /// Records a commit like [AuthorCounts::add_commit] and additionally links
+    /// every navigator with every other navigator, as they worked together in a mob.
+    pub fn add_mob_commit(&mut self, driver: usize, navigators: &[usize], changes: DiffStats) {
+        self.add_commit(driver, navigators, changes);
+        for (lhs, rhs) in distinct_navigators(driver, navigators)
+            .into_iter()
+            .tuple_combinations()
+        {
+            self.author(lhs).paired_with(rhs).inc_navigator(changes);
+            self.author(rhs).paired_with(lhs).inc_navigator(changes);
+        }
+    }
+
+    /// Records a commit by `driver` and its `navigators` in the period of time that starts at `start`,
+    /// see [crate::date::Bucket::start_of].
+    pub fn add_to_timeline(&mut self, start: i64, driver: usize, navigators: &[usize]) {
+        let navigators = distinct_navigators(driver, navigators);
+        if navigators.is_empty() {
+            self.author(driver).period(start).solo += 1;
+            return;
+        }
+
+        self.author(driver).period(start).paired += 1;
+        for navigator in navigators {
+            self.author(navigator).period(start).paired += 1;
+        }
+    }
+
+    /// Records that `driver` and its `navigators` committed together at `time`, in seconds since the epoch,
+    /// for every pair of them that was counted by [AuthorCounts::add_commit] or
+    /// [AuthorCounts::add_mob_commit], see [PairedWith::last_paired].
+    pub fn add_last_paired(&mut self, time: i64, driver: usize, navigators: &[usize]) {
+        let group = std::iter::once(driver)
+            .chain(distinct_navigators(driver, navigators))
+            .collect_vec();
+        for (&author, &co_author) in group.iter().tuple_combinations() {
+            for (author, co_author) in [(author, co_author), (co_author, author)] {
+                let paired_with = self
+                    .0
+                    .get_mut(&author)
+                    .and_then(|counts| counts.co_authors.get_mut(&co_author));
+                if let Some(paired_with) = paired_with {
+                    paired_with.last_paired = paired_with.last_paired.max(Some(time));
+                }
+            }
+        }
+    }
+
+    /// Records a commit by `driver` that was co-authored by `navigator`.
+    /// This only counts the pair, use [AuthorCounts::add_commit] to also count the commit.
+    pub fn add_pair(&mut self, driver: usize, navigator: usize, changes: DiffStats) {
+        if driver != navigator {
+            self.author(driver)
+                .paired_with(navigator)
+                .inc_driver(changes);
+            self.author(navigator)
+                .paired_with(driver)
+                .inc_navigator(changes);
+        }
+    }
+
+    /// Records a commit by `author` that was reviewed by `reviewer`.
+    pub fn add_review(&mut self, author: usize, reviewer: usize) {
+        if author != reviewer {
+            self.author(author).paired_with(reviewer).inc_reviewed();
+            self.author(reviewer).paired_with(author).inc_reviewer();
+        }
+    }
+
+    /// Removes all authors for which `keep` returns `false`, including their pairs with the other authors.
+    pub fn retain(&mut self, keep: impl Fn(usize) -> bool) {
+        self.0.retain(|&author, _| keep(author));
+        for counts in self.0.values_mut() {
+            counts.co_authors.retain(|&co_author, _| keep(co_author));
+        }
+    }
+
+    fn author(&mut self, author: usize) -> &mut PairingCounts {
+        self.0.entry(author).or_default()
+    }
+
+    /// Iterates over all authors and their pairings, with the author ids resolved to names.
+    pub fn into_resolving_iter(
+        self,
+        string_cache: &StringCache,
+    ) -> impl Iterator<Item = (&str, PairingCounts)> {
+        ResolvingAuthorCountsIter {
+            string_cache,
+            inner: self.into_iter(),
+        }
+    }
+}
+
+/// The navigators without the driver and without duplicates, e.g. from different spellings.
+fn distinct_navigators(driver: usize, navigators: &[usize]) -> Vec<usize> {
+    navigators
+        .iter()
+        .copied()
+        .filter(|&navigator| navigator != driver)
+        .unique()
+        .collect()
+}
+
+impl Index<usize> for AuthorCounts {
+    type Output = PairingCounts;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[&index]
+    }
+}
+
+impl IntoIterator for AuthorCounts {
+    type Item = (usize, PairingCounts);
+
+    type IntoIter = <HashMap<usize, PairingCounts> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+struct ResolvingAuthorCountsIter<'a> {
+    string_cache: &'a StringCache,
+    inner: <HashMap<usize, PairingCounts> as IntoIterator>::IntoIter,
+}
+
+impl<'a> Iterator for ResolvingAuthorCountsIter<'a> {
+    type Item = (&'a str, PairingCounts);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (author, counts) = self.inner.next()?;
+        let author = &self.string_cache[author];
+        Some((author, counts))
+    }
+}
+
+/// How often a single author paired with any other author, and how often they worked alone.
+#[derive(Debug, Default, Clone)]
+pub struct PairingCounts {
+    co_authors: FxHashMap<usize, PairedWith>,
+    solo: u32,
+    paired: u32,
+    group_sizes: BTreeMap<usize, u32>,
+    timeline: BTreeMap<i64, Activity>,
+}
+
+impl PairingCounts {
+    pub(crate) fn paired_with(&mut self, author: usize) -> &mut PairedWith {
+        self.co_authors.entry(author).or_default()
+    }
+
+    /// Commits of the author without any co-author.
+    pub fn solo(&self) -> u32 {
+        self.solo
+    }
+
+    /// Commits where the author was the driver or a navigator, each counted once
+    /// regardless of the number of co-authors.
+    pub fn paired(&self) -> u32 {
+        self.paired
+    }
+
+    /// How many commits the author worked on by the number of people who worked on them,
+    /// including the author, ordered by size. Solo commits have a size of `1`.
+    pub fn group_sizes(&self) -> impl Iterator<Item = (usize, u32)> + '_ {
+        self.group_sizes
+            .iter()
+            .map(|(&size, &commits)| (size, commits))
+    }
+
+    fn add_group(&mut self, size: usize) {
+        *self.group_sizes.entry(size).or_default() += 1;
+    }
+
+    /// The solo and paired commits of the author by the start of the period they were created in,
+    /// ordered by time. Periods without commits are skipped.
+    /// Is empty unless [crate::ScanOptions::bucket] is set.
+    pub fn timeline(&self) -> impl Iterator<Item = (i64, Activity)> + '_ {
+        self.timeline
+            .iter()
+            .map(|(&start, &activity)| (start, activity))
+    }
+
+    fn period(&mut self, start: i64) -> &mut Activity {
+        self.timeline.entry(start).or_default()
+    }
+
+    /// All commits the author worked on, alone or with others.
+    pub fn commits(&self) -> u32 {
+        self.solo + self.paired
+    }
+
+    /// The share of the author's commits that were paired, between `0.0` and `1.0`.
+    /// Is `None` if the author only appears as a reviewer.
+    pub fn pairing_ratio(&self) -> Option<f64> {
+        match self.commits() {
+            0 => None,
+            commits => Some(f64::from(self.paired) / f64::from(commits)),
+        }
+    }
+
+    /// The number of distinct co-authors.
+    pub fn len(&self) -> usize {
+        self.co_authors.len()
+    }
+
+    /// Whether the author never paired with anyone.
+    pub fn is_empty(&self) -> bool {
+        self.co_authors.is_empty()
+    }
+
+    /// The highest number of commits with a single co-author in the given relationship.
+    pub fn max_value(&self, relationship: Relationship) -> u32 {
+        self.co_authors
+            .values()
+            .map(|c| c.total_of(relationship))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Iterates over all co-authors by their id in the [StringCache].
+    pub fn iter(&self) -> impl Iterator<Item = (usize, PairedWith)> + '_ {
+        self.co_authors
+            .iter()
+            .map(|(&author, &paired_with)| (author, paired_with))
+    }
+
+    /// Iterates over all co-authors, with their ids resolved to names.
+    pub fn resolving_iter<'counts, 'name: 'counts>(
+        &'counts self,
+        string_cache: &'name StringCache,
+    ) -> impl Iterator<Item = (&'name str, PairedWith)> + 'counts {
+        ResolvingPairingCountsIter::new(string_cache, self.co_authors.iter())
+    }
+}
+
+impl Index<usize> for PairingCounts {
+    type Output = PairedWith;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.co_authors[&index]
+    }
+}
+
+struct ResolvingPairingCountsIter<'name, T> {
+    string_cache: &'name StringCache,
+    inner: T,
+}
+
+impl<'name, T> ResolvingPairingCountsIter<'name, T> {
+    fn new(string_cache: &'name StringCache, inner: T) -> Self {
+        Self {
+            string_cache,
+            inner,
+        }
+    }
+}
+
+impl<'name, 'counts, T> Iterator for ResolvingPairingCountsIter<'name, T>
+where
+    T: Iterator<Item = (&'counts usize, &'counts PairedWith)>,
+{
+    type Item = (&'name str, PairedWith);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (author, counts) = self.inner.next()?;
+        let author = &self.string_cache[*author];
+        Some((author, *counts))
+    }
+}
+
+/// The commits of an author within a period of time, see [PairingCounts::timeline].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Activity {
+    /// Commits of the author without any co-author.
+    pub solo: u32,
+    /// Commits where the author was the driver or a navigator.
+    pub paired: u32,
+}
+
+/// The number of commits of two authors together.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct PairedWith {
+    /// Commits where the author was the driver, i.e. the commit author.
+    pub as_driver: u32,
+    /// All commits of both authors together.
+    pub total: u32,
+    /// Commits of the author that were reviewed by the other author.
+    pub as_reviewed: u32,
+    /// All commits of either author that were reviewed by the other one.
+    pub reviews: u32,
+    /// The changes of the commits where the author was the driver.
+    pub changes_as_driver: DiffStats,
+    /// The changes of all commits of both authors together.
+    pub changes: DiffStats,
+    /// The time of the latest commit of both authors together, in seconds since the epoch,
+    /// see [AuthorCounts::add_last_paired].
+    pub last_paired: Option<i64>,
+}
+
+impl PairedWith {
+    /// Commits where the author was a navigator, i.e. a co-author.
+    /// In a mob, this includes commits where both authors were navigators.
+    pub fn as_navigator(&self) -> u32 {
+        self.total - self.as_driver
+    }
+
+    /// Commits of the other author that were reviewed by the author.
+    pub fn as_reviewer(&self) -> u32 {
+        self.reviews - self.as_reviewed
+    }
+
+    /// All commits of both authors in the given relationship.
+    pub fn total_of(&self, relationship: Relationship) -> u32 {
+        match relationship {
+            Relationship::Pairing => self.total,
+            Relationship::Review => self.reviews,
+        }
+    }
+
+    /// Commits in the given relationship where the author wrote the commit,
+    /// i.e. was the driver or was reviewed.
+    pub fn as_author_of(&self, relationship: Relationship) -> u32 {
+        match relationship {
+            Relationship::Pairing => self.as_driver,
+            Relationship::Review => self.as_reviewed,
+        }
+    }
+
+    /// All commits of both authors in the given relationship, weighted by `weight`.
+    /// Reviews are always weighted by commits.
+    pub fn weighted_total_of(&self, relationship: Relationship, weight: Weight) -> u64 {
+        match relationship {
+            Relationship::Pairing => weighted(self.total, self.changes, weight),
+            Relationship::Review => self.reviews.into(),
+        }
+    }
+
+    /// Commits in the given relationship where the author wrote the commit, weighted by `weight`.
+    /// Reviews are always weighted by commits.
+    pub fn weighted_as_author_of(&self, relationship: Relationship, weight: Weight) -> u64 {
+        match relationship {
+            Relationship::Pairing => weighted(self.as_driver, self.changes_as_driver, weight),
+            Relationship::Review => self.as_reviewed.into(),
+        }
+    }
+
+    fn inc_driver(&mut self, changes: DiffStats) {
+        self.as_driver += 1;
+        self.changes_as_driver += changes;
+        self.inc_navigator(changes);
+    }
+
+    fn inc_navigator(&mut self, changes: DiffStats) {
+        self.total += 1;
+        self.changes += changes;
+    }
+
+    fn inc_reviewed(&mut self) {
+        self.as_reviewed += 1;
+        self.reviews += 1;
+    }
+
+    fn inc_reviewer(&mut self) {
+        self.reviews += 1;
+    }
+}
+
+fn weighted(commits: u32, changes: DiffStats, weight: Weight) -> u64 {
+    match weight {
+        Weight::Commits => commits.into(),
+        Weight::Lines => changes.lines(),
+        Weight::Files => changes.files,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_commit() {
+        let (alice, bob, charlie) = (0, 1, 2);
+        let mut counts = AuthorCounts::default();
+        counts.add_commit(alice, &[], DiffStats::default());
+        counts.add_commit(alice, &[alice], DiffStats::default());
+        counts.add_commit(alice, &[bob, charlie], DiffStats::default());
+        counts.add_commit(bob, &[alice, alice], DiffStats::default());
+
+        assert_eq!(counts[alice].solo(), 2);
+        assert_eq!(counts[alice].paired(), 2);
+        assert_eq!(counts[alice].pairing_ratio(), Some(0.5));
+        assert_eq!(counts[alice][bob].total, 2);
+        assert_eq!(counts[alice][bob].as_driver, 1);
+        assert_eq!(counts[charlie].solo(), 0);
+        assert_eq!(counts[charlie].paired(), 1);
+    }
+
+    #[test]
+    fn test_add_mob_commit() {
+        let (alice, bob, charlie) = (0, 1, 2);
+        let mut counts = AuthorCounts::default();
+        counts.add_commit(alice, &[bob, charlie], DiffStats::default());
+        assert_eq!(counts[bob].len(), 1);
+
+        counts.add_mob_commit(alice, &[bob, charlie], DiffStats::default());
+        assert_eq!(counts[alice][bob].total, 2);
+        assert_eq!(counts[bob][charlie].total, 1);
+        assert_eq!(counts[bob][charlie].as_driver, 0);
+        assert_eq!(counts[charlie][bob].as_navigator(), 1);
+        assert_eq!(counts[bob].paired(), 2);
+    }
+
+    #[test]
+    fn test_group_sizes() {
+        let (alice, bob, charlie) = (0, 1, 2);
+        let mut counts = AuthorCounts::default();
+        counts.add_commit(alice, &[], DiffStats::default());
+        counts.add_commit(alice, &[bob], DiffStats::default());
+        counts.add_mob_commit(bob, &[alice, charlie], DiffStats::default());
+        counts.add_commit(charlie, &[alice, bob, bob], DiffStats::default());
+
+        let sizes = |author: usize| counts[author].group_sizes().collect::<Vec<_>>();
+        assert_eq!(sizes(alice), vec![(1, 1), (2, 1), (3, 2)]);
+        assert_eq!(sizes(charlie), vec![(3, 2)]);
+    }
+
+    #[test]
+    fn test_weighted() {
+        let (alice, bob) = (0, 1);
+        let changes = |insertions, deletions, files| DiffStats {
+            insertions,
+            deletions,
+            files,
+        };
+        let mut counts = AuthorCounts::default();
+        counts.add_commit(alice, &[bob], changes(10, 5, 2));
+        counts.add_commit(bob, &[alice], changes(100, 0, 1));
+
+        let paired_with = counts[alice][bob];
+        let total = |weight| paired_with.weighted_total_of(Relationship::Pairing, weight);
+        let as_author = |weight| paired_with.weighted_as_author_of(Relationship::Pairing, weight);
+        assert_eq!((total(Weight::Commits), as_author(Weight::Commits)), (2, 1));
+        assert_eq!((total(Weight::Lines), as_author(Weight::Lines)), (115, 15));
+        assert_eq!((total(Weight::Files), as_author(Weight::Files)), (3, 2));
+    }
+
+    #[test]
+    fn test_timeline() {
+        let (alice, bob) = (0, 1);
+        let mut counts = AuthorCounts::default();
+        counts.add_to_timeline(100, alice, &[]);
+        counts.add_to_timeline(100, alice, &[bob]);
+        counts.add_to_timeline(50, bob, &[alice]);
+
+        let activity = |solo, paired| Activity { solo, paired };
+        assert_eq!(
+            counts[alice].timeline().collect::<Vec<_>>(),
+            vec![(50, activity(0, 1)), (100, activity(1, 1))]
+        );
+        assert_eq!(
+            counts[bob].timeline().collect::<Vec<_>>(),
+            vec![(50, activity(0, 1)), (100, activity(0, 1))]
+        );
+    }
+
+    #[test]
+    fn test_last_paired() {
+        let (alice, bob, charlie) = (0, 1, 2);
+        let mut counts = AuthorCounts::default();
+        counts.add_commit(alice, &[bob, charlie], DiffStats::default());
+        counts.add_last_paired(200, alice, &[bob, charlie]);
+        counts.add_commit(bob, &[alice], DiffStats::default());
+        counts.add_last_paired(100, bob, &[alice]);
+
+        assert_eq!(counts[alice][bob].last_paired, Some(200));
+        assert_eq!(counts[bob][alice].last_paired, Some(200));
+        assert_eq!(counts[charlie][alice].last_paired, Some(200));
+        // the navigators did not pair without `add_mob_commit`
+        assert_eq!(counts[bob].iter().count(), 1);
+    }
+
+    #[test]
+    fn test_retain() {
+        let (alice, bob, charlie) = (0, 1, 2);
+        let mut counts = AuthorCounts::default();
+        counts.add_commit(alice, &[bob, charlie], DiffStats::default());
+        counts.retain(|author| author != charlie);
+
+        assert_eq!(counts.0.len(), 2);
+        assert_eq!(counts[alice].len(), 1);
+        assert_eq!(counts[alice][bob].total, 1);
+        // the commit itself is still counted
+        assert_eq!(counts[alice].paired(), 1);
+    }
+
+    #[test]
+    fn test_reviewer_only() {
+        let (alice, bob) = (0, 1);
+        let mut counts = AuthorCounts::default();
+        counts.add_review(alice, bob);
+
+        assert_eq!(counts[bob].commits(), 0);
+        assert_eq!(counts[bob].pairing_ratio(), None);
+    }
+}
@@ -0,0 +1,272 @@
+use chrono::{
+    DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+};
+use git2::Commit;
+
+use crate::Error;
+
+/// Which timestamp of a commit is used for date filtering.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum DateField {
+    /// The time the commit was created, e.g. after a rebase
+    #[default]
+    Committer,
+    /// The time the changes were originally authored
+    Author,
+}
+
+impl DateField {
+    pub(crate) fn time_of(self, commit: &Commit<'_>) -> i64 {
+        match self {
+            DateField::Committer => commit.committer().when().seconds(),
+            DateField::Author => commit.author().when().seconds(),
+        }
+    }
+}
+
+/// A period of time that commits are grouped into, see [crate::ScanOptions::bucket].
+///
+/// Periods are in UTC and weeks start on Monday.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Bucket {
+    /// Calendar days
+    Day,
+    /// Weeks from Monday to Sunday
+    Week,
+    /// Calendar months
+    Month,
+    /// Quarters of the year, starting in January, April, July, and October
+    Quarter,
+}
+
+impl Bucket {
+    /// The start of the period that contains the timestamp, in seconds since the epoch.
+    pub fn start_of(self, seconds: i64) -> i64 {
+        let day = date_of(seconds);
+        let start = match self {
+            Bucket::Day => day,
+            Bucket::Week => day - Duration::days(day.weekday().num_days_from_monday().into()),
+            Bucket::Month => day.with_day(1).unwrap_or(day),
+            Bucket::Quarter => {
+                NaiveDate::from_ymd_opt(day.year(), day.month0() / 3 * 3 + 1, 1).unwrap_or(day)
+            }
+        };
+        day_bound(start, Bound::Since)
+    }
+
+    /// The start of the period after the one that starts at `start`.
+    pub fn next(self, start: i64) -> i64 {
+        let day = date_of(start);
+        let next = match self {
+            Bucket::Day => day.checked_add_signed(Duration::days(1)),
+            Bucket::Week => day.checked_add_signed(Duration::weeks(1)),
+            Bucket::Month => day.checked_add_months(Months::new(1)),
+            Bucket::Quarter => day.checked_add_months(Months::new(3)),
+        };
+        next.map_or(i64::MAX, |next| day_bound(next, Bound::Since))
+    }
+
+    /// A short label for the period that starts at `start`, e.g. `2022-10-03`, `2022-W40`,
+    /// `2022-10`, or `2022-Q4`.
+    pub fn label(self, start: i64) -> String {
+        let day = date_of(start);
+        match self {
+            Bucket::Day => day.format("%Y-%m-%d").to_string(),
+            Bucket::Week => day.format("%G-W%V").to_string(),
+            Bucket::Month => day.format("%Y-%m").to_string(),
+            Bucket::Quarter => format!("{}-Q{}", day.year(), day.month0() / 3 + 1),
+        }
+    }
+}
+
+fn date_of(seconds: i64) -> NaiveDate {
+    match Utc.timestamp_opt(seconds, 0).single() {
+        Some(time) => time.date_naive(),
+        None => NaiveDate::MIN,
+    }
+}
+
+/// Parses the lower bound of a date filter, see [parse].
+pub fn parse_since(input: &str) -> Result<i64, Error> {
+    parse(input, Bound::Since, Utc::now())
+}
+
+/// Parses the upper bound of a date filter, see [parse].
+pub fn parse_until(input: &str) -> Result<i64, Error> {
+    parse(input, Bound::Until, Utc::now())
+}
+
+/// Formats a timestamp so that it can be parsed again by [parse].
+pub fn format(seconds: i64) -> String {
+    match Utc.timestamp_opt(seconds, 0).single() {
+        Some(time) => time.format("%Y-%m-%d %H:%M:%S").to_string(),
+        None => seconds.to_string(),
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Bound {
+    Since,
+    Until,
+}
+
+/// Parses a date into seconds since the epoch.
+///
+/// Accepted are ISO dates (`2022-10-01`), date times (`2022-10-01 12:00:00`, `2022-10-01T12:00:00+02:00`),
+/// the keywords `now`, `today`, and `yesterday`, as well as relative expressions like `2 weeks ago`
+/// or `3.days.ago`. Dates without a time zone are interpreted as UTC.
+///
+/// A plain date covers the whole day, i.e. it starts at midnight when used as lower bound
+/// and ends right before the next midnight when used as upper bound.
+fn parse(input: &str, bound: Bound, now: DateTime<Utc>) -> Result<i64, Error> {
+    let input = input.trim();
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Ok(time.timestamp());
+    }
+
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(input, format) {
+            return Ok(time.and_utc().timestamp());
+        }
+    }
+
+    let day = match input.to_ascii_lowercase().as_str() {
+        "now" => return Ok(now.timestamp()),
+        "today" => Some(now.date_naive()),
+        "yesterday" => now.date_naive().pred_opt(),
+        _ => NaiveDate::parse_from_str(input, "%Y-%m-%d").ok(),
+    };
+    if let Some(day) = day {
+        return Ok(day_bound(day, bound));
+    }
+
+    parse_relative(input, now)
+        .map(|time| time.timestamp())
+        .ok_or_else(|| Error::InvalidDate(input.to_string()))
+}
+
+fn day_bound(day: NaiveDate, bound: Bound) -> i64 {
+    let start = day.and_time(NaiveTime::MIN).and_utc().timestamp();
+    match bound {
+        Bound::Since => start,
+        Bound::Until => start + Duration::days(1).num_seconds() - 1,
+    }
+}
+
+/// Parses expressions of the form `<amount> <unit> ago`, with `.` being accepted as separator as well.
+fn parse_relative(input: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let input = input.to_ascii_lowercase().replace('.', " ");
+    let mut parts = input.split_whitespace();
+
+    let amount = parts.next()?.parse::<u32>().ok()?;
+    let unit = parts.next()?;
+    match (parts.next(), parts.next()) {
+        (Some("ago"), None) => {}
+        _ => return None,
+    }
+
+    let unit = unit.strip_suffix('s').unwrap_or(unit);
+    let amount_i64 = i64::from(amount);
+    match unit {
+        "second" | "sec" => now.checked_sub_signed(Duration::seconds(amount_i64)),
+        "minute" | "min" => now.checked_sub_signed(Duration::minutes(amount_i64)),
+        "hour" => now.checked_sub_signed(Duration::hours(amount_i64)),
+        "day" => now.checked_sub_signed(Duration::days(amount_i64)),
+        "week" => now.checked_sub_signed(Duration::weeks(amount_i64)),
+        "month" => now.checked_sub_months(Months::new(amount)),
+        "year" => now.checked_sub_months(Months::new(amount.checked_mul(12)?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2022, 10, 15, 12, 30, 0).unwrap()
+    }
+
+    fn since(input: &str) -> String {
+        format(parse(input, Bound::Since, now()).unwrap())
+    }
+
+    fn until(input: &str) -> String {
+        format(parse(input, Bound::Until, now()).unwrap())
+    }
+
+    #[test]
+    fn test_absolute_dates() {
+        assert_eq!(since("2022-10-01"), "2022-10-01 00:00:00");
+        assert_eq!(until("2022-10-01"), "2022-10-01 23:59:59");
+        assert_eq!(since("2022-10-01 08:15"), "2022-10-01 08:15:00");
+        assert_eq!(until("2022-10-01T08:15:30"), "2022-10-01 08:15:30");
+        assert_eq!(since("2022-10-01T08:15:30+02:00"), "2022-10-01 06:15:30");
+    }
+
+    #[test]
+    fn test_keywords() {
+        assert_eq!(since("now"), "2022-10-15 12:30:00");
+        assert_eq!(since("today"), "2022-10-15 00:00:00");
+        assert_eq!(until("Yesterday"), "2022-10-14 23:59:59");
+    }
+
+    #[test]
+    fn test_relative_dates() {
+        assert_eq!(since("2 weeks ago"), "2022-10-01 12:30:00");
+        assert_eq!(since("1 day ago"), "2022-10-14 12:30:00");
+        assert_eq!(since("3.hours.ago"), "2022-10-15 09:30:00");
+        assert_eq!(since("2 months ago"), "2022-08-15 12:30:00");
+        assert_eq!(until("1 year ago"), "2021-10-15 12:30:00");
+    }
+
+    #[test]
+    fn test_invalid_dates() {
+        for input in [
+            "",
+            "last sprint",
+            "2 weeks",
+            "two weeks ago",
+            "2022-13-01",
+            "2 fortnights ago",
+        ] {
+            assert!(parse(input, Bound::Since, now()).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_buckets() {
+        // Wednesday, 2022-10-05 13:14:15 UTC
+        let time = parse("2022-10-05 13:14:15", Bound::Since, now()).unwrap();
+        let start = |bucket: Bucket| format(bucket.start_of(time));
+        assert_eq!(start(Bucket::Day), "2022-10-05 00:00:00");
+        assert_eq!(start(Bucket::Week), "2022-10-03 00:00:00");
+        assert_eq!(start(Bucket::Month), "2022-10-01 00:00:00");
+        assert_eq!(start(Bucket::Quarter), "2022-10-01 00:00:00");
+
+        let next = |bucket: Bucket| format(bucket.next(bucket.start_of(time)));
+        assert_eq!(next(Bucket::Day), "2022-10-06 00:00:00");
+        assert_eq!(next(Bucket::Week), "2022-10-10 00:00:00");
+        assert_eq!(next(Bucket::Month), "2022-11-01 00:00:00");
+        assert_eq!(next(Bucket::Quarter), "2023-01-01 00:00:00");
+
+        let label = |bucket: Bucket| bucket.label(bucket.start_of(time));
+        assert_eq!(label(Bucket::Day), "2022-10-05");
+        assert_eq!(label(Bucket::Week), "2022-W40");
+        assert_eq!(label(Bucket::Month), "2022-10");
+        assert_eq!(label(Bucket::Quarter), "2022-Q4");
+    }
+}
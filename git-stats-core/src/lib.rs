@@ -0,0 +1,93 @@
+//! Collects pairing statistics from the `Co-authored-by` trailers of a Git repository.
+//!
+//! This crate contains the scanning logic of `git-stats` without any user interface,
+//! so that the statistics can be used in other tools.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use git_stats_core::{IdentityOptions, Normalization, Repo, ScanOptions};
+//!
+//! let mut repo = Repo::open(
+//!     Some("path/to/repository".into()),
+//!     IdentityOptions {
+//!         use_mailmap: true,
+//!         normalization: Normalization::DEFAULT.to_vec(),
+//!         ..IdentityOptions::default()
+//!     },
+//! )?;
+//!
+//! let counts = repo.extract_coauthors(&ScanOptions::default())?;
+//! for (author, pairings) in counts.into_resolving_iter(repo.string_cache()) {
+//!     for (co_author, paired_with) in pairings.resolving_iter(repo.string_cache()) {
+//!         println!("{} paired with {} {} times", author, co_author, paired_with.total);
+//!     }
+//! }
+//! # Ok::<(), git_stats_core::Error>(())
+//! ```
+
+pub use co_authors::CoAuthorError;
+pub use git2;
+
+pub use crate::{
+    author_counts::{
+        Activity, AuthorCounts, DiffStats, PairedWith, PairingCounts, Relationship, Weight,
+    },
+    date::{Bucket, DateField},
+    lint::{MalformedTrailer, MessageProblem, UnlistedCoAuthor},
+    normalize::Normalization,
+    repo::{Explanation, Identity, IdentityOptions, KnownAuthor, Repo},
+    roster::{Member, Roster},
+    scan::{MergePolicy, ScanOptions, TrailerMode, DEFAULT_PAIR_TRAILERS, DEFAULT_REVIEW_TRAILERS},
+    stringcache::StringCache,
+};
+
+mod author_counts;
+pub mod date;
+mod lint;
+mod normalize;
+mod replacements;
+mod repo;
+mod roster;
+mod scan;
+mod stringcache;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors that can occur while scanning a repository.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Not in a Git repository.")]
+    NotInGitRepository,
+    #[error("Invalid range: `{range}`. Git error: {}", .error.message())]
+    InvalidRange { range: String, error: git2::Error },
+    #[error("Could not read mailmap: {}", .0.message())]
+    Mailmap(git2::Error),
+    #[error("Git error: {}", .0.message())]
+    Git(git2::Error),
+    #[error("Could not compute the changes of commit {commit}. Git error: {}", .error.message())]
+    DiffStats { commit: String, error: git2::Error },
+    #[error("Invalid replacement pattern `{pattern}`: {source}")]
+    InvalidPattern {
+        pattern: String,
+        source: regex::Error,
+    },
+    #[error(
+        "Invalid date: `{0}`. Use a date like `2022-10-01` or a relative date like `2 weeks ago`."
+    )]
+    InvalidDate(String),
+    #[error("Could not determine the author of the commit. Set `user.name` in the Git config.")]
+    MissingAuthor,
+    #[error("`{entry}` belongs to both `{first}` and `{second}` on the roster.")]
+    AmbiguousRosterEntry {
+        entry: String,
+        first: String,
+        second: String,
+    },
+}
+
+impl From<git2::Error> for Error {
+    fn from(error: git2::Error) -> Self {
+        Error::Git(error)
+    }
+}
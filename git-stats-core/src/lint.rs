@@ -0,0 +1,202 @@
+use std::fmt;
+
+use co_authors::{CoAuthor, CoAuthorError, Mode};
+
+use crate::ScanOptions;
+
+/// A trailer that names a navigator or reviewer, but could not be parsed, see [crate::Repo::lint].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MalformedTrailer {
+    /// The abbreviated id of the commit.
+    pub commit: String,
+    /// The author of the commit, as it appears in the commit.
+    pub author: String,
+    /// The offending line of the commit message.
+    pub line: String,
+    /// Why the trailer could not be parsed.
+    pub error: CoAuthorError,
+}
+
+/// A navigator that is not on the roster, see [crate::Repo::unlisted_co_authors].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnlistedCoAuthor {
+    /// The abbreviated id of the commit.
+    pub commit: String,
+    /// The author of the commit, as it appears in the commit.
+    pub author: String,
+    /// The navigator, as it appears in the trailer.
+    pub co_author: String,
+}
+
+/// A problem with the message of a commit that is about to be created, see [crate::Repo::check_message].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageProblem {
+    /// A trailer that names a navigator or reviewer, but could not be parsed.
+    Malformed { line: String, error: CoAuthorError },
+    /// The author named themselves as co-author.
+    SelfCoAuthored { co_author: String },
+    /// The co-author does not appear in any previous commit, which is most likely a typo.
+    Unknown { co_author: String },
+    /// The co-author is not a member of the roster, which is checked instead of the history
+    /// if there is a roster.
+    NotOnRoster { co_author: String },
+}
+
+impl fmt::Display for MessageProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageProblem::Malformed { line, error } => write!(f, "`{}`: {}", line, error),
+            MessageProblem::SelfCoAuthored { co_author } => {
+                write!(f, "`{}` is the author of the commit.", co_author)
+            }
+            MessageProblem::Unknown { co_author } => {
+                write!(f, "`{}` is not a known author.", co_author)
+            }
+            MessageProblem::NotOnRoster { co_author } => {
+                write!(f, "`{}` is not on the roster.", co_author)
+            }
+        }
+    }
+}
+
+/// Returns the offending lines of a commit message together with the reason why they were ignored.
+///
+/// These are trailers with a navigator or reviewer key whose value is not a `Name <mail>`,
+/// as well as lines that start with such a key, but lack the separator, e.g. `Co-authored-by Alice`.
+/// Only the last paragraph is checked, as that is where git looks for trailers. All of its lines
+/// are checked though, as a malformed trailer can prevent git from recognizing the trailer block.
+pub(crate) fn malformed_trailers(
+    message: &str,
+    options: &ScanOptions,
+) -> Vec<(String, CoAuthorError)> {
+    let paragraph = co_authors::last_paragraph(message);
+    let mut malformed = paragraph
+        .iter()
+        .flat_map(|line| co_authors::trailers_in(line, Mode::Lenient))
+        .filter(|trailer| options.relationship(trailer.key).is_some())
+        .filter_map(|trailer| {
+            let error = CoAuthor::from_value(trailer.value).err()?;
+            let line = format!("{}: {}", trailer.key, trailer.unfolded_value());
+            Some((line, error))
+        })
+        .collect::<Vec<_>>();
+
+    let keys = options
+        .pair_trailers
+        .iter()
+        .chain(&options.review_trailers)
+        .collect::<Vec<_>>();
+    for line in paragraph.iter().map(|line| line.trim()) {
+        if keys.iter().any(|key| lacks_separator(line, key)) {
+            malformed.push((line.to_string(), CoAuthorError::MissingTrailerKey));
+        }
+    }
+
+    malformed
+}
+
+/// Whether the line starts with the trailer key, but the key is not followed by a `:`.
+fn lacks_separator(line: &str, key: &str) -> bool {
+    match line.get(..key.len()) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(key) => {
+            let rest = &line[key.len()..];
+            let continues_key = rest.starts_with(|c: char| c.is_alphanumeric() || c == '-');
+            let rest = rest.trim_start();
+            !continues_key && !rest.is_empty() && !rest.starts_with(':')
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(message: &str) -> Vec<(String, CoAuthorError)> {
+        let options = ScanOptions {
+            review_trailers: vec!["Reviewed-by".into()],
+            ..ScanOptions::default()
+        };
+        malformed_trailers(message, &options)
+    }
+
+    #[test]
+    fn test_valid() {
+        let message = "Subject\n\nCo-authored-by: Alice <alice@wonderland.org>\nReviewed-by: Bob <bob@wonderland.org>\nFixes: #42\n";
+        assert_eq!(lint(message), vec![]);
+    }
+
+    #[test]
+    fn test_malformed() {
+        let message = "Subject\n\nCo-authored-by: Alice\nCo-authored-by: <bob@wonderland.org>\nReviewed-by: Charlie\n";
+        assert_eq!(
+            lint(message),
+            vec![
+                (
+                    "Co-authored-by: Alice".to_string(),
+                    CoAuthorError::MissingMail
+                ),
+                (
+                    "Co-authored-by: <bob@wonderland.org>".to_string(),
+                    CoAuthorError::MissingName
+                ),
+                (
+                    "Reviewed-by: Charlie".to_string(),
+                    CoAuthorError::MissingMail
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_separator() {
+        let message = "Subject\n\nCo-authored-by Alice <alice@wonderland.org>\n";
+        assert_eq!(
+            lint(message),
+            vec![(
+                "Co-authored-by Alice <alice@wonderland.org>".to_string(),
+                CoAuthorError::MissingTrailerKey
+            )]
+        );
+    }
+
+    #[test]
+    fn test_broken_trailer_block() {
+        let message = "Subject\n\nCo-authored-by: Alice\nCo-authored-by Bob <bob@wonderland.org>\n";
+        assert_eq!(
+            lint(message),
+            vec![
+                (
+                    "Co-authored-by: Alice".to_string(),
+                    CoAuthorError::MissingMail
+                ),
+                (
+                    "Co-authored-by Bob <bob@wonderland.org>".to_string(),
+                    CoAuthorError::MissingTrailerKey
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_in_prose() {
+        let message = "Subject\n\nCo-authored-by trailers are now parsed from the last paragraph\nand Reviewed-by: is no longer ignored.\n\nCo-authored-by: Alice <alice@wonderland.org>\n";
+        assert_eq!(lint(message), vec![]);
+        assert_eq!(
+            lint("Co-authored-by Alice <alice@wonderland.org>\n"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_verbose_diff() {
+        let message = "Subject\n\nCo-authored-by: Alice <alice@wonderland.org>\n# ------------------------ >8 ------------------------\n\nCo-authored-by Bob\n";
+        assert_eq!(lint(message), vec![]);
+    }
+
+    #[test]
+    fn test_quoted_trailer() {
+        let message = "Revert\n\nThis reverts the commit with\nCo-authored-by: Alice <alice@wonderland.org>\n";
+        assert_eq!(lint(message), vec![]);
+    }
+}
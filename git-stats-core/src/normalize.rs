@@ -0,0 +1,178 @@
+use std::borrow::Cow;
+
+use itertools::Itertools;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// A single step of the normalization that is applied to every author name.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Normalization {
+    /// Do not normalize names, they are displayed verbatim
+    None,
+    /// Compose characters into their canonical form (Unicode NFC)
+    Nfc,
+    /// Decompose characters into their compatibility form (Unicode NFKD)
+    Nfkd,
+    /// Transliterate German umlauts and `ß`, e.g. `Jörg Müller` becomes `Joerg Mueller`
+    Umlauts,
+    /// Remove diacritics, e.g. `é` becomes `e`
+    StripDiacritics,
+    /// Remove leading and trailing whitespace and replace any other whitespace by a single space
+    CollapseWhitespace,
+    /// Compare names regardless of case by converting them to lower case
+    CaseFold,
+}
+
+impl Normalization {
+    /// The steps that are applied if nothing else is configured.
+    pub const DEFAULT: [Normalization; 3] = [
+        Normalization::Nfc,
+        Normalization::Umlauts,
+        Normalization::CollapseWhitespace,
+    ];
+
+    fn apply(self, name: &str) -> Cow<'_, str> {
+        match self {
+            Normalization::None => name.into(),
+            Normalization::Nfc => name.nfc().collect::<String>().into(),
+            Normalization::Nfkd => name.nfkd().collect::<String>().into(),
+            Normalization::Umlauts => replace_umlauts(name),
+            Normalization::StripDiacritics => name
+                .nfd()
+                .filter(|c| !is_combining_mark(*c))
+                .nfc()
+                .collect::<String>()
+                .into(),
+            Normalization::CollapseWhitespace => name.split_whitespace().join(" ").into(),
+            Normalization::CaseFold => name.to_lowercase().into(),
+        }
+    }
+}
+
+fn replace_umlauts(input: &str) -> Cow<'_, str> {
+    fn replacement(c: char) -> Option<&'static str> {
+        match c {
+            'Ä' => Some("Ae"),
+            'ä' => Some("ae"),
+            'Ö' => Some("Oe"),
+            'ö' => Some("oe"),
+            'Ü' => Some("Ue"),
+            'ü' => Some("ue"),
+            'ß' => Some("ss"),
+            _ => None,
+        }
+    }
+
+    if !input.chars().any(|c| replacement(c).is_some()) {
+        return input.into();
+    }
+
+    let mut new_string = String::with_capacity(input.len());
+    for c in input.chars() {
+        match replacement(c) {
+            Some(replacement) => new_string.push_str(replacement),
+            None => new_string.push(c),
+        }
+    }
+    new_string.into()
+}
+
+/// Applies the configured normalization steps in order.
+pub(crate) struct Normalizer(Vec<Normalization>);
+
+impl Normalizer {
+    pub(crate) fn new(steps: Vec<Normalization>) -> Self {
+        Self(steps)
+    }
+
+    pub(crate) fn normalize<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        let mut normalized = Cow::Borrowed(name);
+        for step in &self.0 {
+            let changed = match step.apply(&normalized) {
+                Cow::Owned(name) => Some(name),
+                Cow::Borrowed(_) => None,
+            };
+            if let Some(name) = changed {
+                normalized = Cow::Owned(name);
+            }
+        }
+        normalized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(steps: &[Normalization], name: &str) -> String {
+        Normalizer::new(steps.to_vec()).normalize(name).into_owned()
+    }
+
+    #[test]
+    fn test_nfc() {
+        assert_eq!(normalize(&[Normalization::Nfc], "Zoe\u{308}"), "Zo\u{eb}");
+        assert_eq!(normalize(&[Normalization::Nfc], "Zo\u{eb}"), "Zo\u{eb}");
+    }
+
+    #[test]
+    fn test_nfkd() {
+        assert_eq!(normalize(&[Normalization::Nfkd], "\u{fb01}o"), "fio");
+        assert_eq!(normalize(&[Normalization::Nfkd], "Zo\u{eb}"), "Zoe\u{308}");
+    }
+
+    #[test]
+    fn test_strip_diacritics() {
+        let steps = [Normalization::StripDiacritics];
+        assert_eq!(normalize(&steps, "Jos\u{e9}"), "Jose");
+        assert_eq!(normalize(&steps, "Zoe\u{308}"), "Zoe");
+        assert_eq!(normalize(&steps, "Jörg Müller"), "Jorg Muller");
+        assert_eq!(normalize(&steps, "Łukasz"), "Łukasz");
+    }
+
+    #[test]
+    fn test_umlauts() {
+        assert_eq!(replace_umlauts("Jörg Müller"), "Joerg Mueller");
+        assert_eq!(replace_umlauts("Jorge"), "Jorge");
+    }
+
+    #[test]
+    fn test_collapse_whitespace() {
+        let steps = [Normalization::CollapseWhitespace];
+        assert_eq!(normalize(&steps, "  Alice \t Keys "), "Alice Keys");
+    }
+
+    #[test]
+    fn test_case_fold() {
+        assert_eq!(
+            normalize(&[Normalization::CaseFold], "Alice KEYS"),
+            "alice keys"
+        );
+    }
+
+    #[test]
+    fn test_default() {
+        assert_eq!(
+            normalize(&Normalization::DEFAULT, " José  Jo\u{308}rg "),
+            "José Joerg"
+        );
+        // identities that only differ in how the umlauts are written are still merged
+        assert_eq!(
+            normalize(&Normalization::DEFAULT, "Jörg Müller"),
+            normalize(&Normalization::DEFAULT, "Joerg Mueller")
+        );
+    }
+
+    #[test]
+    fn test_none() {
+        assert_eq!(normalize(&[], " Jos\u{e9} "), " Jos\u{e9} ");
+        assert_eq!(
+            normalize(&[Normalization::None], " Jos\u{e9} "),
+            " Jos\u{e9} "
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use std::borrow::Cow;
+
+use regex::Regex;
+
+use crate::Error;
+
+const CASE_INSENSITIVE: &str = "i:";
+const GLOB: &str = "glob:";
+const REGEX: &str = "re:";
+
+/// Rules that replace author names, e.g. to merge different spellings of the same person.
+///
+/// Rules are checked in order and the first matching rule wins.
+/// The pattern of a rule matches the whole name and can be one of
+///
+/// - `Alice`: the exact name
+/// - `i:alice`: the name, ignoring case
+/// - `glob:a*`: a glob ignoring case, `*` matches any number of characters and `?` a single one
+/// - `re:^(\w+) S(mith)?$`: a regular expression
+///
+/// Replacements of glob and regex rules can refer to the text matched by a wildcard or capture group
+/// with `$1`, `$2`, and so on. Regex rules can also refer to named groups, e.g. `${first}`.
+pub(crate) struct Replacements(Vec<Rule>);
+
+impl Replacements {
+    pub(crate) fn new(rules: Vec<(String, String)>) -> Result<Self, Error> {
+        let rules = rules
+            .into_iter()
+            .map(|(pattern, replacement)| Rule::new(pattern, replacement))
+            .collect::<Result<_, _>>()?;
+        Ok(Self(rules))
+    }
+
+    /// Applies the first matching rule and returns the replaced name together with that rule.
+    pub(crate) fn replace<'a>(&'a self, name: &'a str) -> (Cow<'a, str>, Option<&'a Rule>) {
+        self.0
+            .iter()
+            .find_map(|rule| Some((rule.apply(name)?, Some(rule))))
+            .unwrap_or((Cow::Borrowed(name), None))
+    }
+}
+
+/// A single replacement rule, see [Replacements].
+pub(crate) struct Rule {
+    pattern: String,
+    matcher: Matcher,
+    replacement: String,
+}
+
+enum Matcher {
+    Exact,
+    CaseInsensitive(String),
+    Regex(Regex),
+}
+
+impl Rule {
+    fn new(pattern: String, replacement: String) -> Result<Self, Error> {
+        let regex = |expression: &str| {
+            Regex::new(expression).map_err(|source| Error::InvalidPattern {
+                pattern: pattern.clone(),
+                source,
+            })
+        };
+
+        let matcher = if let Some(name) = pattern.strip_prefix(CASE_INSENSITIVE) {
+            Matcher::CaseInsensitive(name.to_lowercase())
+        } else if let Some(glob) = pattern.strip_prefix(GLOB) {
+            Matcher::Regex(regex(&glob_to_regex(glob))?)
+        } else if let Some(expression) = pattern.strip_prefix(REGEX) {
+            // the pattern has to match the whole name
+            Matcher::Regex(regex(&format!("^(?:{})$", expression))?)
+        } else {
+            Matcher::Exact
+        };
+
+        Ok(Self {
+            pattern,
+            matcher,
+            replacement,
+        })
+    }
+
+    /// The pattern as it was written by the user.
+    pub(crate) fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    fn apply<'a>(&'a self, name: &'a str) -> Option<Cow<'a, str>> {
+        let matches = match &self.matcher {
+            Matcher::Exact => name == self.pattern,
+            Matcher::CaseInsensitive(pattern) => name.to_lowercase() == *pattern,
+            Matcher::Regex(regex) => {
+                return if regex.is_match(name) {
+                    Some(regex.replace(name, self.replacement.as_str()))
+                } else {
+                    None
+                };
+            }
+        };
+        matches.then_some(Cow::Borrowed(self.replacement.as_str()))
+    }
+}
+
+/// Translates a glob into a case-insensitive regex where every wildcard is a capture group.
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("(?i)^");
+    let mut literal = String::new();
+    for c in glob.chars() {
+        let wildcard = match c {
+            '*' => "(.*)",
+            '?' => "(.)",
+            c => {
+                literal.push(c);
+                continue;
+            }
+        };
+        regex.push_str(&regex::escape(&literal));
+        regex.push_str(wildcard);
+        literal.clear();
+    }
+    regex.push_str(&regex::escape(&literal));
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replacements(rules: &[(&str, &str)]) -> Replacements {
+        let rules = rules
+            .iter()
+            .map(|(pattern, replacement)| (pattern.to_string(), replacement.to_string()))
+            .collect();
+        Replacements::new(rules).unwrap()
+    }
+
+    fn replace(replacements: &Replacements, name: &str) -> (String, Option<String>) {
+        let (name, rule) = replacements.replace(name);
+        (
+            name.into_owned(),
+            rule.map(|rule| rule.pattern().to_string()),
+        )
+    }
+
+    #[test]
+    fn test_exact() {
+        let replacements = replacements(&[("alice", "Alice")]);
+        assert_eq!(
+            replace(&replacements, "alice"),
+            ("Alice".into(), Some("alice".into()))
+        );
+        assert_eq!(replace(&replacements, "Alice"), ("Alice".into(), None));
+        assert_eq!(replace(&replacements, "alice2"), ("alice2".into(), None));
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let replacements = replacements(&[("i:ALICE", "Alice Keys")]);
+        assert_eq!(replace(&replacements, "alice").0, "Alice Keys");
+        assert_eq!(replace(&replacements, "Alice").0, "Alice Keys");
+        assert_eq!(replace(&replacements, "Alice K").0, "Alice K");
+    }
+
+    #[test]
+    fn test_glob() {
+        let replacements =
+            replacements(&[("glob:a* smith", "Alice Smith"), ("glob:b?b *", "Bob $2")]);
+        assert_eq!(replace(&replacements, "A. Smith").0, "Alice Smith");
+        assert_eq!(replace(&replacements, "alice smith").0, "Alice Smith");
+        assert_eq!(replace(&replacements, "Bob Builder").0, "Bob Builder");
+        assert_eq!(replace(&replacements, "bib builder").0, "Bob builder");
+        assert_eq!(replace(&replacements, "Alice Smithers").0, "Alice Smithers");
+    }
+
+    #[test]
+    fn test_regex() {
+        let replacements = replacements(&[
+            (r"re:(?i)(?P<first>\w+)\s+smith", "${first} Smith"),
+            (r"re:[a-z]+", "lower"),
+        ]);
+        assert_eq!(replace(&replacements, "alice   smith").0, "alice Smith");
+        assert_eq!(replace(&replacements, "bob").0, "lower");
+        assert_eq!(replace(&replacements, "bob!").0, "bob!");
+        assert_eq!(
+            replace(&replacements, "Alice SMITH").1.as_deref(),
+            Some(r"re:(?i)(?P<first>\w+)\s+smith")
+        );
+    }
+
+    #[test]
+    fn test_first_rule_wins() {
+        let replacements = replacements(&[("i:alice", "First"), ("alice", "Second")]);
+        assert_eq!(replace(&replacements, "alice").0, "First");
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        let rules = vec![("re:(unclosed".to_string(), "x".to_string())];
+        assert!(Replacements::new(rules).is_err());
+    }
+}
@@ -0,0 +1,847 @@
+use std::path::PathBuf;
+
+use co_authors::CoAuthor;
+use fxhash::FxHashSet;
+use git2::{Commit, Mailmap, Repository, Signature, Sort, Time};
+use indexmap::{IndexMap, IndexSet};
+use itertools::Itertools;
+
+use crate::{
+    lint::{self, MalformedTrailer, MessageProblem, UnlistedCoAuthor},
+    normalize::{Normalization, Normalizer},
+    replacements::Replacements,
+    AuthorCounts, DiffStats, Error, Member, Relationship, Result, Roster, ScanOptions, StringCache,
+};
+
+/// The rule of an [Explanation] for authors that were found on the roster by their name or email.
+const ROSTER_RULE: &str = "roster";
+
+/// How commit authors and co-authors are identified.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Identity {
+    /// Authors with the same display name are the same person
+    #[default]
+    Name,
+    /// Authors with the same email address are the same person, the most frequent name is displayed.
+    /// Falls back to the name if the email is missing.
+    Email,
+}
+
+/// Options that control how authors and co-authors are resolved to a person.
+#[derive(Debug, Clone, Default)]
+pub struct IdentityOptions {
+    /// Pairs of name patterns and their replacements, see [Replacements].
+    pub replacements: Vec<(String, String)>,
+    /// Whether authors are identified by name or by email.
+    pub identity: Identity,
+    /// Whether names and emails are resolved through the repository's mailmap first.
+    pub use_mailmap: bool,
+    /// The steps to normalize names after the replacements have been applied.
+    pub normalization: Vec<Normalization>,
+    /// The members of the team. Authors are looked up on the roster by email and name
+    /// after the mailmap, and by name again after the replacements.
+    /// Members are displayed by their name on the roster.
+    pub roster: Roster,
+}
+
+/// A Git repository together with the rules to resolve its authors.
+pub struct Repo {
+    repository: Repository,
+    authors: Authors,
+    string_cache: StringCache,
+}
+
+impl Repo {
+    /// Finds the Git repository at the given path or in the environment, e.g. the current directory.
+    pub fn discover(path: Option<PathBuf>) -> Result<Repository> {
+        let repository = path
+            .map_or_else(Repository::open_from_env, Repository::discover)
+            .map_err(|_| Error::NotInGitRepository)?;
+        Ok(repository)
+    }
+
+    /// Finds the Git repository like [Repo::discover] and prepares it for scanning.
+    pub fn open(path: Option<PathBuf>, options: IdentityOptions) -> Result<Self> {
+        Self::new(Self::discover(path)?, options)
+    }
+
+    /// Prepares the repository for scanning, authors are resolved according to the `options`.
+    pub fn new(repository: Repository, options: IdentityOptions) -> Result<Self> {
+        let IdentityOptions {
+            replacements,
+            identity,
+            use_mailmap,
+            normalization,
+            roster,
+        } = options;
+
+        // reads `.mailmap` as well as the `mailmap.file` and `mailmap.blob` config
+        let mailmap = if use_mailmap {
+            Some(repository.mailmap().map_err(Error::Mailmap)?)
+        } else {
+            None
+        };
+
+        Ok(Repo {
+            repository,
+            authors: Authors {
+                mailmap,
+                replacements: Replacements::new(replacements)?,
+                normalizer: Normalizer::new(normalization),
+                identity,
+                roster,
+            },
+            string_cache: StringCache::new(),
+        })
+    }
+
+    /// The underlying Git repository.
+    pub fn repository(&self) -> &Repository {
+        &self.repository
+    }
+
+    /// The members of the team, see [IdentityOptions::roster].
+    pub fn roster(&self) -> &Roster {
+        &self.authors.roster
+    }
+
+    /// The names of all authors that have been seen so far.
+    pub fn string_cache(&self) -> &StringCache {
+        &self.string_cache
+    }
+
+    /// Counts how often each author paired with each co-author in the commits selected by `options`.
+    pub fn extract_coauthors(&mut self, options: &ScanOptions) -> Result<AuthorCounts> {
+        let repository = &self.repository;
+        let authors = &self.authors;
+        let string_cache = &mut self.string_cache;
+
+        let mut author_counts = Self::commits(repository, options)?.try_fold(
+            AuthorCounts::default(),
+            |counts, commit| {
+                Self::find_and_add_navigators(
+                    repository,
+                    authors,
+                    string_cache,
+                    counts,
+                    commit,
+                    options,
+                )
+            },
+        )?;
+
+        if options.filters_members() {
+            let string_cache = &self.string_cache;
+            author_counts.retain(|author| {
+                authors
+                    .roster
+                    .member(&string_cache[author])
+                    .is_some_and(|member| options.includes_member(member))
+            });
+        }
+
+        Ok(author_counts)
+    }
+
+    /// The members of the roster that are counted with the `options`, see [ScanOptions::members_only].
+    pub fn members(&self, options: &ScanOptions) -> Vec<&Member> {
+        self.authors
+            .roster
+            .members()
+            .iter()
+            .filter(|member| options.includes_member(member))
+            .collect()
+    }
+
+    /// Lists every distinct author and co-author in the scanned commits
+    /// together with the identity they are resolved to.
+    pub fn explain_identities(&self, options: &ScanOptions) -> Result<Vec<Explanation>> {
+        let mut signatures = IndexSet::<(String, Option<String>)>::new();
+        for commit in Self::commits(&self.repository, options)? {
+            let author = commit.author();
+            if let Some(name) = author.name() {
+                let _ = signatures.insert((name.to_string(), author.email().map(String::from)));
+            }
+            for (_, co_author) in
+                Self::get_collaborators(commit.message().unwrap_or_default(), options)
+            {
+                let mail = co_author.mail.map(String::from);
+                let _ = signatures.insert((co_author.name.to_string(), mail));
+            }
+        }
+
+        let explanations = signatures
+            .into_iter()
+            .sorted()
+            .map(|(name, mail)| {
+                let resolved = self.authors.resolve(&name, mail.as_deref());
+                Explanation {
+                    mailmap: resolved
+                        .mailmap
+                        .map(|(name, mail)| signature(&name, mail.as_deref())),
+                    rule: resolved.rule,
+                    identity: signature(&resolved.name, resolved.key.as_deref()),
+                    name,
+                    mail,
+                }
+            })
+            .collect();
+
+        Ok(explanations)
+    }
+
+    /// Lists every trailer in the scanned commits that names a navigator or reviewer,
+    /// but is ignored because it could not be parsed.
+    pub fn lint(&self, options: &ScanOptions) -> Result<Vec<MalformedTrailer>> {
+        let mut malformed = Vec::new();
+        for commit in Self::commits(&self.repository, options)? {
+            let message = commit.message().unwrap_or_default();
+            for (line, error) in lint::malformed_trailers(message, options) {
+                let author = commit.author();
+                malformed.push(MalformedTrailer {
+                    commit: short_id(&commit),
+                    author: signature(author.name().unwrap_or_default(), author.email()),
+                    line,
+                    error,
+                });
+            }
+        }
+        Ok(malformed)
+    }
+
+    /// Lists every navigator in the scanned commits that is not on the roster.
+    /// Nothing is listed if there is no roster.
+    pub fn unlisted_co_authors(&self, options: &ScanOptions) -> Result<Vec<UnlistedCoAuthor>> {
+        let mut unlisted = Vec::new();
+        if self.authors.roster.is_empty() {
+            return Ok(unlisted);
+        }
+
+        for commit in Self::commits(&self.repository, options)? {
+            let message = commit.message().unwrap_or_default();
+            for (relationship, co_author) in Self::get_collaborators(message, options) {
+                if relationship != Relationship::Pairing
+                    || self
+                        .authors
+                        .is_listed(&co_author.name, co_author.mail.as_deref())
+                {
+                    continue;
+                }
+                let author = commit.author();
+                unlisted.push(UnlistedCoAuthor {
+                    commit: short_id(&commit),
+                    author: signature(author.name().unwrap_or_default(), author.email()),
+                    co_author: signature(&co_author.name, co_author.mail.as_deref()),
+                });
+            }
+        }
+        Ok(unlisted)
+    }
+
+    /// Lists every distinct author and co-author in the scanned commits,
+    /// together with the most recent name and email address they used.
+    ///
+    /// Authors are merged by their [Repo::identity_key], but keep the name as it appears in the commits
+    /// or the mailmap, so that it can be written into new trailers without the replacements
+    /// and normalization.
+    pub fn known_authors(&self, options: &ScanOptions) -> Result<Vec<KnownAuthor>> {
+        let mut known = IndexMap::<String, KnownAuthor>::new();
+        let mut add = |name: &str, mail: Option<&str>| {
+            let resolved = self.authors.resolve(name, mail);
+            let (name, mail) = match resolved.mailmap {
+                Some((mapped_name, mapped_mail)) => (mapped_name, mapped_mail),
+                None => (name.to_string(), mail.map(String::from)),
+            };
+            let key = resolved.key.unwrap_or(resolved.name);
+            let entry = known.entry(key.clone()).or_insert_with(|| KnownAuthor {
+                key,
+                name,
+                mail: None,
+            });
+            if entry.mail.is_none() {
+                entry.mail = mail.filter(|mail| !mail.is_empty());
+            }
+        };
+
+        // commits are visited from newest to oldest, so the first email seen is the most recent one
+        for commit in Self::commits(&self.repository, options)? {
+            let author = commit.author();
+            if let Some(name) = author.name() {
+                add(name, author.email());
+            }
+            let message = commit.message().unwrap_or_default();
+            for (_, co_author) in Self::get_collaborators(message, options) {
+                add(&co_author.name, co_author.mail.as_deref());
+            }
+        }
+
+        let known = known
+            .into_values()
+            .sorted_by(|lhs, rhs| lhs.name.cmp(&rhs.name))
+            .collect();
+        Ok(known)
+    }
+
+    /// The key that identifies the author in the counts, which is their resolved name,
+    /// or their email with [Identity::Email].
+    pub fn identity_key(&self, name: &str, mail: Option<&str>) -> String {
+        self.authors.key(name, mail)
+    }
+
+    /// Checks the message of a commit that is about to be created by the given author.
+    ///
+    /// Besides malformed trailers, this finds co-authors that are the author themselves
+    /// and co-authors that never appeared in the scanned commits before.
+    /// The latter is skipped if the repository does not have any commits yet.
+    /// If there is a roster, co-authors are checked against the roster instead of the history.
+    pub fn check_message(
+        &self,
+        message: &str,
+        author_name: &str,
+        author_mail: Option<&str>,
+        options: &ScanOptions,
+    ) -> Result<Vec<MessageProblem>> {
+        let mut problems = lint::malformed_trailers(message, options)
+            .into_iter()
+            .map(|(line, error)| MessageProblem::Malformed { line, error })
+            .collect_vec();
+
+        let has_roster = !self.authors.roster.is_empty();
+        let has_history = !has_roster && self.repository.head().is_ok();
+        let known = if has_history {
+            self.known_keys(options)?
+        } else {
+            FxHashSet::default()
+        };
+
+        let author = self.authors.key(author_name, author_mail);
+        let co_authors = Self::get_collaborators(message, options)
+            .into_iter()
+            .filter(|(relationship, _)| *relationship == Relationship::Pairing);
+        for (_, co_author) in co_authors {
+            let key = self.authors.key(&co_author.name, co_author.mail.as_deref());
+            let is_listed = self
+                .authors
+                .is_listed(&co_author.name, co_author.mail.as_deref());
+            let co_author = signature(&co_author.name, co_author.mail.as_deref());
+            if key == author {
+                problems.push(MessageProblem::SelfCoAuthored { co_author });
+            } else if has_roster && !is_listed {
+                problems.push(MessageProblem::NotOnRoster { co_author });
+            } else if has_history && !known.contains(&key) {
+                problems.push(MessageProblem::Unknown { co_author });
+            }
+        }
+
+        Ok(problems)
+    }
+
+    /// The keys of every author, navigator, and reviewer in the scanned commits.
+    /// Only the commit messages are read, and every distinct name and email is resolved once,
+    /// so that this is fast enough for the commit hook even in large histories.
+    fn known_keys(&self, options: &ScanOptions) -> Result<FxHashSet<String>> {
+        let mut signatures = FxHashSet::<(String, Option<String>)>::default();
+        let mut add = |name: &str, mail: Option<&str>| {
+            let _ = signatures.insert((name.to_string(), mail.map(String::from)));
+        };
+        for commit in Self::commits(&self.repository, options)? {
+            let author = commit.author();
+            if let Some(name) = author.name() {
+                add(name, author.email());
+            }
+            let message = commit.message().unwrap_or_default();
+            for (_, collaborator) in Self::get_collaborators(message, options) {
+                add(&collaborator.name, collaborator.mail.as_deref());
+            }
+        }
+
+        Ok(signatures
+            .iter()
+            .map(|(name, mail)| self.authors.key(name, mail.as_deref()))
+            .collect())
+    }
+
+    /// The author of a new commit, taken from `GIT_AUTHOR_NAME` and `GIT_AUTHOR_EMAIL`
+    /// or from the `user.name` and `user.email` config.
+    pub fn default_author(&self) -> Result<(String, Option<String>)> {
+        let from_env = |var| std::env::var(var).ok().filter(|value| !value.is_empty());
+        let (config_name, config_mail) = match self.repository.signature() {
+            Ok(signature) => (
+                signature.name().map(String::from),
+                signature.email().map(String::from),
+            ),
+            Err(_) => (None, None),
+        };
+        let name = from_env("GIT_AUTHOR_NAME")
+            .or(config_name)
+            .ok_or(Error::MissingAuthor)?;
+        let mail = from_env("GIT_AUTHOR_EMAIL").or(config_mail);
+        Ok((name, mail))
+    }
+
+    fn commits<'repo>(
+        repository: &'repo Repository,
+        options: &'repo ScanOptions,
+    ) -> Result<impl Iterator<Item = Commit<'repo>> + 'repo> {
+        let mut revwalk = repository.revwalk()?;
+        // newest first, which the callers rely on to find the most recent name and email of an author
+        revwalk.set_sorting(Sort::TIME)?;
+        match &options.range {
+            Some(range) => {
+                revwalk
+                    .push_range(range.as_str())
+                    .map_err(|error| Error::InvalidRange {
+                        range: range.clone(),
+                        error,
+                    })?
+            }
+            None => revwalk.push_head()?,
+        };
+
+        let commits = revwalk
+            .filter_map(move |oid| repository.find_commit(oid.ok()?).ok())
+            .filter(move |commit| options.includes(commit));
+
+        Ok(commits)
+    }
+
+    fn find_and_add_navigators(
+        repository: &Repository,
+        authors: &Authors,
+        string_cache: &mut StringCache,
+        mut author_counts: AuthorCounts,
+        commit: Commit<'_>,
+        options: &ScanOptions,
+    ) -> Result<AuthorCounts> {
+        let changes = if options.diff_stats {
+            diff_stats(repository, &commit).map_err(|error| Error::DiffStats {
+                commit: short_id(&commit),
+                error,
+            })?
+        } else {
+            DiffStats::default()
+        };
+        // commits without a message or an author name are skipped
+        let _ = Self::try_find_and_add_navigators(
+            authors,
+            string_cache,
+            &mut author_counts,
+            commit,
+            changes,
+            options,
+        );
+        Ok(author_counts)
+    }
+
+    fn try_find_and_add_navigators(
+        authors: &Authors,
+        string_cache: &mut StringCache,
+        author_counts: &mut AuthorCounts,
+        commit: Commit<'_>,
+        changes: DiffStats,
+        options: &ScanOptions,
+    ) -> Option<()> {
+        let commit_message = commit.message()?;
+        let author = commit.author();
+        let author = authors.id(string_cache, author.name()?, author.email());
+
+        let mut navigators = Vec::new();
+        for (relationship, collaborator) in Self::get_collaborators(commit_message, options) {
+            let collaborator = authors.id(
+                string_cache,
+                &collaborator.name,
+                collaborator.mail.as_deref(),
+            );
+            match relationship {
+                Relationship::Pairing => navigators.push(collaborator),
+                Relationship::Review => author_counts.add_review(author, collaborator),
+            }
+        }
+        if options.mob {
+            author_counts.add_mob_commit(author, &navigators, changes);
+        } else {
+            author_counts.add_commit(author, &navigators, changes);
+        }
+        let time = options.date_field.time_of(&commit);
+        author_counts.add_last_paired(time, author, &navigators);
+        if let Some(bucket) = options.bucket {
+            let start = bucket.start_of(time);
+            author_counts.add_to_timeline(start, author, &navigators);
+        }
+
+        Some(())
+    }
+
+    /// Returns the navigators and reviewers of a commit.
+    fn get_collaborators<'a>(
+        commit_message: &'a str,
+        options: &ScanOptions,
+    ) -> Vec<(Relationship, CoAuthor<'a>)> {
+        co_authors::trailers_in(commit_message, options.trailers.into())
+            .filter_map(|trailer| {
+                let relationship = options.relationship(trailer.key)?;
+                let collaborator = CoAuthor::from_trailer(&trailer).ok()?;
+                Some((relationship, collaborator))
+            })
+            .collect()
+    }
+}
+
+/// Maps the name and mail of an author to their id in the [StringCache].
+struct Authors {
+    mailmap: Option<Mailmap>,
+    replacements: Replacements,
+    normalizer: Normalizer,
+    identity: Identity,
+    roster: Roster,
+}
+
+impl Authors {
+    fn id(&self, string_cache: &mut StringCache, name: &str, mail: Option<&str>) -> usize {
+        let resolved = self.resolve(name, mail);
+        match resolved.key {
+            Some(key) => string_cache.intern_with_label(key, resolved.name),
+            None => string_cache.intern(resolved.name),
+        }
+    }
+
+    /// The key under which the author is stored in the [StringCache].
+    fn key(&self, name: &str, mail: Option<&str>) -> String {
+        let resolved = self.resolve(name, mail);
+        resolved.key.unwrap_or(resolved.name)
+    }
+
+    /// Whether the author is found on the roster.
+    fn is_listed(&self, name: &str, mail: Option<&str>) -> bool {
+        self.resolve(name, mail).listed
+    }
+
+    fn resolve(&self, name: &str, mail: Option<&str>) -> Resolved {
+        let mailmapped = self.resolve_mailmap(name, mail).and_then(|signature| {
+            let mapped_name = signature.name().unwrap_or(name);
+            let mapped_mail = signature.email().filter(|mail| !mail.is_empty());
+            if mapped_name == name && mapped_mail == mail {
+                None
+            } else {
+                Some((mapped_name.to_string(), mapped_mail.map(String::from)))
+            }
+        });
+        let (name, mail) = match &mailmapped {
+            Some((name, mail)) => (name.as_str(), mail.as_deref()),
+            None => (name, mail),
+        };
+        if let Some(member) = self.roster.find(name, mail) {
+            return Resolved::member(mailmapped, Some(ROSTER_RULE.to_string()), member);
+        }
+
+        let (name, rule) = self.replacements.replace(name);
+        let rule = rule.map(|rule| rule.pattern().to_string());
+        let name = self.normalizer.normalize(&name).into_owned();
+        if let Some(member) = self.roster.find(&name, None) {
+            return Resolved::member(mailmapped, rule, member);
+        }
+        let mail = mail.map(str::trim).filter(|mail| !mail.is_empty());
+
+        let key = match (self.identity, mail) {
+            // the replacements are rules for names, emails are merged through the mailmap
+            (Identity::Email, Some(mail)) => Some(mail.to_lowercase()),
+            _ => None,
+        };
+
+        Resolved {
+            mailmap: mailmapped,
+            rule,
+            name,
+            key,
+            listed: false,
+        }
+    }
+
+    fn resolve_mailmap(&self, name: &str, mail: Option<&str>) -> Option<Signature<'static>> {
+        let mailmap = self.mailmap.as_ref()?;
+        // every mailmap entry is looked up by the email, so authors without one are never mapped,
+        // which also avoids the signature that libgit2 rejects for an empty email
+        let mail = mail.map(str::trim).filter(|mail| !mail.is_empty())?;
+        let signature = Signature::new(name, mail, &Time::new(0, 0)).ok()?;
+        mailmap.resolve_signature(&signature).ok()
+    }
+}
+
+/// An author after applying the mailmap and the replacements.
+struct Resolved {
+    /// Name and mail from the mailmap, if the mailmap changed any of them.
+    mailmap: Option<(String, Option<String>)>,
+    /// The pattern of the replacement rule that matched the name.
+    rule: Option<String>,
+    /// The name that is displayed.
+    name: String,
+    /// The key in the [StringCache] if the author is not identified by name.
+    key: Option<String>,
+    /// Whether the author was found on the roster.
+    listed: bool,
+}
+
+impl Resolved {
+    /// Members of the roster are always identified by their name on the roster.
+    fn member(
+        mailmap: Option<(String, Option<String>)>,
+        rule: Option<String>,
+        member: &Member,
+    ) -> Self {
+        Resolved {
+            mailmap,
+            rule,
+            name: member.name.clone(),
+            key: None,
+            listed: true,
+        }
+    }
+}
+
+/// An author that appears in the history, see [Repo::known_authors].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownAuthor {
+    /// The key that identifies the author, see [Repo::identity_key].
+    pub key: String,
+    /// The name of the author in their most recent commit, after the mailmap.
+    pub name: String,
+    /// The email address from the most recent commit of the author, if any.
+    pub mail: Option<String>,
+}
+
+/// How a single author or co-author was resolved, see [Repo::explain_identities].
+#[derive(Debug, Clone)]
+pub struct Explanation {
+    /// The name as it appears in the commit.
+    pub name: String,
+    /// The email as it appears in the commit.
+    pub mail: Option<String>,
+    /// The signature from the mailmap, if it changed the name or email.
+    pub mailmap: Option<String>,
+    /// The pattern of the replacement rule that matched the name,
+    /// or `roster` if the name or email was found on the roster before any replacement.
+    pub rule: Option<String>,
+    /// The resolved name and, if identified by email, the resolved email.
+    pub identity: String,
+}
+
+/// The changes of a commit compared to its first parent, or to the empty tree for a root commit.
+fn diff_stats(repository: &Repository, commit: &Commit<'_>) -> Result<DiffStats, git2::Error> {
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = repository.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    let stats = diff.stats()?;
+    Ok(DiffStats {
+        insertions: stats.insertions() as u64,
+        deletions: stats.deletions() as u64,
+        files: stats.files_changed() as u64,
+    })
+}
+
+fn short_id(commit: &Commit<'_>) -> String {
+    commit
+        .as_object()
+        .short_id()
+        .ok()
+        .and_then(|id| id.as_str().map(String::from))
+        .unwrap_or_else(|| commit.id().to_string())
+}
+
+fn signature(name: &str, mail: Option<&str>) -> String {
+    match mail {
+        Some(mail) => format!("{} <{}>", name, mail),
+        None => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Runs the check against a new repository in a temporary directory, which is set up first.
+    fn with_repo(
+        test: &str,
+        options: IdentityOptions,
+        setup: impl FnOnce(&std::path::Path, &Repository),
+        check: impl FnOnce(&Repo),
+    ) {
+        let path = std::env::temp_dir().join(format!("git-stats-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        let repository = Repository::init(&path).unwrap();
+        setup(&path, &repository);
+        check(&Repo::new(repository, options).unwrap());
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    fn with_mailmap(test: &str, mailmap: &str, check: impl FnOnce(&Repo)) {
+        let options = IdentityOptions {
+            use_mailmap: true,
+            ..IdentityOptions::default()
+        };
+        let setup = |path: &std::path::Path, _: &Repository| {
+            fs::write(path.join(".mailmap"), mailmap).unwrap()
+        };
+        with_repo(test, options, setup, check);
+    }
+
+    fn commit(repository: &Repository, name: &str, mail: &str, time: i64, message: &str) {
+        let signature = Signature::new(name, mail, &Time::new(time, 0)).unwrap();
+        let tree = repository.index().unwrap().write_tree().unwrap();
+        let tree = repository.find_tree(tree).unwrap();
+        let parent = repository
+            .head()
+            .ok()
+            .map(|head| head.peel_to_commit().unwrap());
+        let parents = parent.iter().collect::<Vec<_>>();
+        let _ = repository
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_known_authors() {
+        let options = IdentityOptions {
+            normalization: vec![Normalization::StripDiacritics],
+            ..IdentityOptions::default()
+        };
+        let setup = |_: &std::path::Path, repository: &Repository| {
+            commit(repository, "Jose", "jose@old.example.com", 100, "First");
+            commit(repository, "Alice", "alice@example.com", 50, "Second");
+            commit(
+                repository,
+                "José",
+                "jose@example.com",
+                200,
+                "Third\n\nCo-authored-by: Alice <alice@wonderland.org>\n",
+            );
+        };
+        with_repo("known-authors", options, setup, |repo| {
+            let known = repo.known_authors(&ScanOptions::default()).unwrap();
+            let known = known
+                .iter()
+                .map(|known| {
+                    (
+                        known.key.as_str(),
+                        known.name.as_str(),
+                        known.mail.as_deref(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                known,
+                vec![
+                    ("Alice", "Alice", Some("alice@wonderland.org")),
+                    // the name is written into new trailers, so it keeps its diacritics
+                    ("Jose", "José", Some("jose@example.com")),
+                ]
+            );
+            assert_eq!(repo.identity_key("Jos\u{e9}", None), "Jose");
+        });
+    }
+
+    #[test]
+    fn test_folded_co_author() {
+        let options = IdentityOptions {
+            normalization: Vec::new(),
+            ..IdentityOptions::default()
+        };
+        let setup = |_: &std::path::Path, repository: &Repository| {
+            let folded = "First\n\nCo-authored-by: Alice\n  Keys <alice@example.com>\n";
+            commit(repository, "Bob", "bob@example.com", 100, folded);
+            let unfolded = "Second\n\nCo-authored-by: Alice Keys <alice@example.com>\n";
+            commit(repository, "Bob", "bob@example.com", 200, unfolded);
+        };
+        with_repo("folded-co-author", options, setup, |repo| {
+            let known = repo.known_authors(&ScanOptions::default()).unwrap();
+            let names = known
+                .iter()
+                .map(|known| known.name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(names, vec!["Alice Keys", "Bob"]);
+        });
+    }
+
+    fn mailmapped(repo: &Repo, name: &str, mail: Option<&str>) -> Option<(String, Option<String>)> {
+        repo.authors.resolve(name, mail).mailmap
+    }
+
+    #[test]
+    fn test_name_rules_skip_emails() {
+        let authors = Authors {
+            mailmap: None,
+            replacements: Replacements::new(vec![("glob:a*".into(), "Alice".into())]).unwrap(),
+            normalizer: Normalizer::new(Vec::new()),
+            identity: Identity::Email,
+            roster: Roster::default(),
+        };
+        let resolved = authors.resolve("Bob", Some("Andy@example.com"));
+        assert_eq!(resolved.name, "Bob");
+        assert_eq!(resolved.key.as_deref(), Some("andy@example.com"));
+        let resolved = authors.resolve("alex", Some("alex@example.com"));
+        assert_eq!(resolved.name, "Alice");
+        assert_eq!(resolved.key.as_deref(), Some("alex@example.com"));
+    }
+
+    #[test]
+    fn test_mailmap_name_only() {
+        with_mailmap("mailmap-name", "Alice Keys <alice@example.com>\n", |repo| {
+            assert_eq!(
+                mailmapped(repo, "alice", Some("alice@example.com")),
+                Some(("Alice Keys".into(), Some("alice@example.com".into())))
+            );
+            assert_eq!(
+                repo.authors
+                    .resolve("alice", Some("alice@example.com"))
+                    .name,
+                "Alice Keys"
+            );
+            assert_eq!(
+                mailmapped(repo, "Alice", Some("alice@wonderland.org")),
+                None
+            );
+        });
+    }
+
+    #[test]
+    fn test_mailmap_email_only() {
+        with_mailmap(
+            "mailmap-email",
+            "<bob@wonderland.org> <bob@example.com>\n",
+            |repo| {
+                assert_eq!(
+                    mailmapped(repo, "Bob", Some("bob@example.com")),
+                    Some(("Bob".into(), Some("bob@wonderland.org".into())))
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn test_mailmap_without_email() {
+        with_mailmap(
+            "mailmap-without-email",
+            "Alice Keys <alice@example.com> Alice <>\n",
+            |repo| {
+                assert_eq!(mailmapped(repo, "Alice", None), None);
+                assert_eq!(mailmapped(repo, "Alice", Some(" ")), None);
+                assert_eq!(repo.authors.resolve("Alice", None).name, "Alice");
+            },
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use fxhash::FxHashMap;
+use itertools::Itertools;
+
+use crate::{Error, Result};
+
+/// A person on the roster, see [Roster].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Member {
+    /// The name that is displayed for all commits of the member.
+    pub name: String,
+    /// The email addresses the member commits with, compared case insensitively.
+    pub emails: Vec<String>,
+    /// Other names the member commits with, compared case insensitively.
+    pub aliases: Vec<String>,
+    /// The team or group the member belongs to.
+    pub team: Option<String>,
+    /// The time the member joined, in seconds since the epoch.
+    pub active_since: Option<i64>,
+    /// The time the member left, in seconds since the epoch.
+    pub active_until: Option<i64>,
+}
+
+impl Member {
+    /// Whether the member was active at any time between `since` and `until`.
+    /// Missing bounds are unbounded.
+    pub fn is_active(&self, since: Option<i64>, until: Option<i64>) -> bool {
+        let joined_in_time = match (self.active_since, until) {
+            (Some(active_since), Some(until)) => active_since <= until,
+            _ => true,
+        };
+        let left_in_time = match (self.active_until, since) {
+            (Some(active_until), Some(since)) => active_until >= since,
+            _ => true,
+        };
+        joined_in_time && left_in_time
+    }
+}
+
+/// The people of a team, who are resolved by their emails or names
+/// before any other rule is applied, see [crate::IdentityOptions::roster].
+#[derive(Debug, Clone, Default)]
+pub struct Roster {
+    members: Vec<Member>,
+    /// The index of the member by their lower case email.
+    by_email: FxHashMap<String, usize>,
+    /// The index of the member by their lower case name and aliases.
+    by_name: FxHashMap<String, usize>,
+    /// The index of the member by their name as it is displayed.
+    by_display_name: FxHashMap<String, usize>,
+}
+
+impl Roster {
+    /// Creates a roster from its members.
+    /// Fails if an email, name, or alias belongs to more than one member.
+    pub fn new(members: Vec<Member>) -> Result<Self> {
+        let mut by_email = FxHashMap::default();
+        let mut by_name = FxHashMap::default();
+        for (index, member) in members.iter().enumerate() {
+            for email in &member.emails {
+                insert(&mut by_email, &members, email, index)?;
+            }
+            for name in std::iter::once(&member.name).chain(&member.aliases) {
+                insert(&mut by_name, &members, name, index)?;
+            }
+        }
+
+        let by_display_name = members
+            .iter()
+            .enumerate()
+            .map(|(index, member)| (member.name.clone(), index))
+            .collect();
+
+        Ok(Roster {
+            members,
+            by_email,
+            by_name,
+            by_display_name,
+        })
+    }
+
+    /// Whether the roster has no members, in which case it does not change how authors are resolved.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// All members in the order of the roster.
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    /// The member with the given email, or else the member with the given name as name or alias.
+    pub fn find(&self, name: &str, mail: Option<&str>) -> Option<&Member> {
+        let by_email = mail.and_then(|mail| self.by_email.get(&mail.trim().to_lowercase()));
+        let index = by_email.or_else(|| self.by_name.get(&name.trim().to_lowercase()))?;
+        Some(&self.members[*index])
+    }
+
+    /// The member with the given name, which is how authors on the roster are displayed.
+    pub fn member(&self, name: &str) -> Option<&Member> {
+        let index = self.by_display_name.get(name)?;
+        Some(&self.members[*index])
+    }
+
+    /// The names of all teams, sorted and without duplicates.
+    pub fn teams(&self) -> Vec<&str> {
+        self.members
+            .iter()
+            .filter_map(|member| member.team.as_deref())
+            .sorted()
+            .dedup()
+            .collect()
+    }
+}
+
+fn insert(
+    index: &mut FxHashMap<String, usize>,
+    members: &[Member],
+    key: &str,
+    member: usize,
+) -> Result<()> {
+    let key = key.trim().to_lowercase();
+    match index.get(&key) {
+        Some(&other) if other != member => Err(Error::AmbiguousRosterEntry {
+            entry: key,
+            first: members[other].name.clone(),
+            second: members[member].name.clone(),
+        }),
+        _ => {
+            let _ = index.insert(key, member);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roster() -> Roster {
+        Roster::new(vec![
+            Member {
+                name: "Alice Keys".into(),
+                emails: vec!["alice@wonderland.org".into()],
+                aliases: vec!["alice".into(), "A. Keys".into()],
+                team: Some("platform".into()),
+                ..Member::default()
+            },
+            Member {
+                name: "Bob".into(),
+                emails: vec!["bob@wonderland.org".into()],
+                team: Some("apps".into()),
+                active_since: Some(100),
+                active_until: Some(200),
+                ..Member::default()
+            },
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_find() {
+        let roster = roster();
+        let name = |name, mail| roster.find(name, mail).map(|member| member.name.as_str());
+        assert_eq!(
+            name("Someone", Some("ALICE@wonderland.org")),
+            Some("Alice Keys")
+        );
+        assert_eq!(name("a. keys", None), Some("Alice Keys"));
+        // the email takes precedence over the name
+        assert_eq!(name("alice", Some("bob@wonderland.org")), Some("Bob"));
+        assert_eq!(name("Charlie", Some("charlie@wonderland.org")), None);
+        assert_eq!(roster.teams(), vec!["apps", "platform"]);
+    }
+
+    #[test]
+    fn test_is_active() {
+        let roster = roster();
+        let bob = roster.member("Bob").unwrap();
+        assert!(bob.is_active(None, None));
+        assert!(bob.is_active(Some(150), None));
+        assert!(bob.is_active(None, Some(100)));
+        assert!(!bob.is_active(Some(201), None));
+        assert!(!bob.is_active(None, Some(99)));
+    }
+
+    #[test]
+    fn test_ambiguous() {
+        let member = |name: &str, alias: &str| Member {
+            name: name.into(),
+            aliases: vec![alias.into()],
+            ..Member::default()
+        };
+        let error = Roster::new(vec![member("Alice", "al"), member("Albert", "AL")]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`al` belongs to both `Alice` and `Albert` on the roster."
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{
+    date::{Bucket, DateField},
+    Member, Relationship,
+};
+use git2::Commit;
+
+/// The trailer key that names a navigator by default.
+pub const DEFAULT_PAIR_TRAILERS: [&str; 1] = [co_authors::CO_AUTHORED_BY];
+
+/// Trailer keys that name a reviewer when reviews are tracked.
+/// `Signed-off-by` is left out, as it is the sign-off of the author, e.g. for the DCO.
+pub const DEFAULT_REVIEW_TRAILERS: [&str; 2] = ["Reviewed-by", "Helped-by"];
+
+/// Options that control which commits are considered when scanning a repository.
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Commit range in the form of `<commit-1>..<commit-2>`, or everything reachable from `HEAD`.
+    pub range: Option<String>,
+    /// How merge commits are treated.
+    pub merges: MergePolicy,
+    /// Only include commits at or after this time, in seconds since the epoch.
+    pub since: Option<i64>,
+    /// Only include commits at or before this time, in seconds since the epoch.
+    pub until: Option<i64>,
+    /// Which timestamp of a commit is compared against `since` and `until`.
+    pub date_field: DateField,
+    /// Where co-authors are looked for in the commit message.
+    pub trailers: TrailerMode,
+    /// Trailer keys that name a navigator of the commit, compared case insensitively.
+    pub pair_trailers: Vec<String>,
+    /// Trailer keys that name a reviewer of the commit, compared case insensitively.
+    /// Reviews are not tracked if this is empty.
+    pub review_trailers: Vec<String>,
+    /// Whether the navigators of a commit also paired with each other, as in mob programming.
+    /// Otherwise, navigators are only counted as pairing with the driver.
+    pub mob: bool,
+    /// Whether the changes of each commit are computed, so that pairs can be weighted by them.
+    /// This requires a diff of every commit against its first parent and is slow for large histories.
+    pub diff_stats: bool,
+    /// Groups the solo and paired commits of each author by the period of time they were created in,
+    /// using the `date_field` of the commits, see [crate::PairingCounts::timeline].
+    pub bucket: Option<Bucket>,
+    /// Only count authors that are members of the roster and active between `since` and `until`,
+    /// see [crate::IdentityOptions::roster]. Pairs with anyone else are dropped.
+    pub members_only: bool,
+    /// Only count members of these teams, which implies `members_only`.
+    pub teams: Vec<String>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            range: None,
+            merges: MergePolicy::default(),
+            since: None,
+            until: None,
+            date_field: DateField::default(),
+            trailers: TrailerMode::default(),
+            pair_trailers: DEFAULT_PAIR_TRAILERS
+                .iter()
+                .map(|&key| key.into())
+                .collect(),
+            review_trailers: Vec::new(),
+            mob: false,
+            diff_stats: false,
+            bucket: None,
+            members_only: false,
+            teams: Vec::new(),
+        }
+    }
+}
+
+impl ScanOptions {
+    pub(crate) fn includes(&self, commit: &Commit<'_>) -> bool {
+        self.merges.includes(commit) && self.in_date_range(commit)
+    }
+
+    /// Which relationship a trailer with the given key stands for, if any.
+    pub(crate) fn relationship(&self, key: &str) -> Option<Relationship> {
+        let matches = |keys: &[String]| keys.iter().any(|k| k.eq_ignore_ascii_case(key));
+        if matches(&self.pair_trailers) {
+            Some(Relationship::Pairing)
+        } else if matches(&self.review_trailers) {
+            Some(Relationship::Review)
+        } else {
+            None
+        }
+    }
+
+    /// Whether authors are filtered by the roster.
+    pub fn filters_members(&self) -> bool {
+        self.members_only || !self.teams.is_empty()
+    }
+
+    /// Whether the member is counted if authors are filtered by the roster.
+    pub fn includes_member(&self, member: &Member) -> bool {
+        let on_team = match &member.team {
+            Some(team) => self.teams.is_empty() || self.teams.contains(team),
+            None => self.teams.is_empty(),
+        };
+        on_team && member.is_active(self.since, self.until)
+    }
+
+    fn in_date_range(&self, commit: &Commit<'_>) -> bool {
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+
+        let time = self.date_field.time_of(commit);
+        self.since.is_none_or(|since| time >= since) && self.until.is_none_or(|until| time <= until)
+    }
+}
+
+/// How merge commits, i.e. commits with more than one parent, are treated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum MergePolicy {
+    /// Ignore merge commits
+    #[default]
+    Skip,
+    /// Count merge commits like any other commit
+    Include,
+    /// Only count merge commits, e.g. for squash-merge based workflows
+    Only,
+}
+
+impl MergePolicy {
+    pub(crate) fn includes(self, commit: &Commit<'_>) -> bool {
+        let is_merge = commit.parent_count() > 1;
+        match self {
+            MergePolicy::Skip => !is_merge,
+            MergePolicy::Include => true,
+            MergePolicy::Only => is_merge,
+        }
+    }
+}
+
+/// Where co-author trailers are looked for in a commit message.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum TrailerMode {
+    /// Only in the trailer block at the end of the message, like `git interpret-trailers`
+    #[default]
+    Strict,
+    /// On any line of the message, including quoted trailers in the body
+    Lenient,
+}
+
+impl From<TrailerMode> for co_authors::Mode {
+    fn from(mode: TrailerMode) -> Self {
+        match mode {
+            TrailerMode::Strict => co_authors::Mode::Strict,
+            TrailerMode::Lenient => co_authors::Mode::Lenient,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relationship() {
+        let options = ScanOptions {
+            pair_trailers: vec!["Co-authored-by".into(), "Paired-with".into()],
+            review_trailers: vec!["Reviewed-by".into()],
+            ..ScanOptions::default()
+        };
+        assert_eq!(
+            options.relationship("co-authored-by"),
+            Some(Relationship::Pairing)
+        );
+        assert_eq!(
+            options.relationship("Paired-With"),
+            Some(Relationship::Pairing)
+        );
+        assert_eq!(
+            options.relationship("Reviewed-by"),
+            Some(Relationship::Review)
+        );
+        assert_eq!(options.relationship("Signed-off-by"), None);
+        assert_eq!(ScanOptions::default().relationship("Reviewed-by"), None);
+
+        let options = ScanOptions {
+            review_trailers: DEFAULT_REVIEW_TRAILERS.map(String::from).to_vec(),
+            ..ScanOptions::default()
+        };
+        assert_eq!(options.relationship("Signed-off-by"), None);
+    }
+}
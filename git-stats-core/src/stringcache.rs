@@ -0,0 +1,134 @@
+use fxhash::{FxBuildHasher, FxHashMap};
+use indexmap::set::IndexSet;
+use std::ops::Index;
+
+/// Interns strings and hands out stable indices for them.
+///
+/// Each entry can optionally carry display labels, e.g. the names used together with an email address.
+/// When labels are present, indexing resolves to the most frequently seen label instead of the key.
+#[derive(Default)]
+pub struct StringCache {
+    index_set: IndexSet<String, FxBuildHasher>,
+    labels: Vec<Option<Labels>>,
+}
+
+impl StringCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns the key and returns its index.
+    pub fn intern<T>(&mut self, k: T) -> usize
+    where
+        T: AsRef<str> + Into<String>,
+    {
+        match self.index_set.get_index_of(k.as_ref()) {
+            Some(idx) => idx,
+            None => {
+                self.labels.push(None);
+                self.index_set.insert_full(k.into()).0
+            }
+        }
+    }
+
+    /// Interns the key and records one more occurrence of the label for it.
+    pub fn intern_with_label<T, L>(&mut self, k: T, label: L) -> usize
+    where
+        T: AsRef<str> + Into<String>,
+        L: AsRef<str> + Into<String>,
+    {
+        let idx = self.intern(k);
+        self.labels[idx]
+            .get_or_insert_with(Labels::default)
+            .add(label);
+        idx
+    }
+
+    /// Whether the key has been interned.
+    pub fn contains(&self, k: &str) -> bool {
+        self.index_set.contains(k)
+    }
+
+    fn get(&self, idx: usize) -> Option<&str> {
+        match self.labels.get(idx) {
+            Some(Some(labels)) => Some(labels.most_frequent()),
+            _ => self.index_set.get_index(idx).map(|s| s.as_str()),
+        }
+    }
+}
+
+impl Index<usize> for StringCache {
+    type Output = str;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get(index) {
+            Some(item) => item,
+            None => panic!("No entry for index {}", index),
+        }
+    }
+}
+
+/// Counts the labels seen for a single key. On a tie, the label that was seen first wins.
+#[derive(Debug, Default)]
+struct Labels {
+    counts: FxHashMap<String, u32>,
+    most_frequent: String,
+    max_count: u32,
+}
+
+impl Labels {
+    fn add<L>(&mut self, label: L)
+    where
+        L: AsRef<str> + Into<String>,
+    {
+        let count = match self.counts.get_mut(label.as_ref()) {
+            Some(count) => {
+                *count += 1;
+                *count
+            }
+            None => {
+                let _ = self.counts.insert(label.as_ref().into(), 1);
+                1
+            }
+        };
+
+        if count > self.max_count {
+            self.max_count = count;
+            if self.most_frequent != label.as_ref() {
+                self.most_frequent = label.into();
+            }
+        }
+    }
+
+    fn most_frequent(&self) -> &str {
+        &self.most_frequent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels() {
+        let mut cache = StringCache::new();
+        let alice = cache.intern_with_label("alice@wonderland.org", "alice");
+        let _ = cache.intern_with_label("alice@wonderland.org", "Alice");
+        let _ = cache.intern_with_label("alice@wonderland.org", "Alice");
+        let bob = cache.intern("Bob");
+
+        assert_eq!(&cache[alice], "Alice");
+        assert_eq!(&cache[bob], "Bob");
+        assert_eq!(cache.intern_with_label("alice@wonderland.org", "A."), alice);
+    }
+
+    #[test]
+    fn test_labels_tie() {
+        let mut cache = StringCache::new();
+        let alice = cache.intern_with_label("alice@wonderland.org", "Alice");
+        let _ = cache.intern_with_label("alice@wonderland.org", "alice");
+
+        assert_eq!(&cache[alice], "Alice");
+    }
+}
@@ -0,0 +1,221 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{
+    date, Bucket, DateField, Format, Identity, MergePolicy, Normalization, Result, TrailerMode,
+    Weight, APPLICATION,
+};
+
+/// Name of the configuration file in the root of the repository.
+const REPOSITORY_CONFIG: &str = ".git-stats.toml";
+/// Name of the user configuration file in `$XDG_CONFIG_HOME/git-stats/`.
+const USER_CONFIG: &str = "config.toml";
+
+/// Defaults that are read from configuration files.
+///
+/// All settings are optional, as options given on the command line take precedence.
+///
+/// # Example
+///
+/// ```toml
+/// range = "v1.0.0..HEAD"
+/// since = "4 weeks ago"
+/// merges = "include"
+/// trailers = "lenient"
+/// pair-trailers = ["Co-authored-by", "Paired-with"]
+/// reviews = true
+/// mob = true
+/// weight = "lines"
+/// bucket = "month"
+/// roster = "team.toml"
+/// teams = ["platform"]
+/// members-only = true
+/// identity = "email"
+/// normalize = ["nfc", "collapse-whitespace"]
+/// format = "json"
+///
+/// [replacements]
+/// "alice" = "Alice Keys"
+/// ```
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct Config {
+    pub(crate) replacements: IndexMap<String, String>,
+    pub(crate) range: Option<String>,
+    pub(crate) since: Option<String>,
+    pub(crate) until: Option<String>,
+    pub(crate) date_field: Option<DateField>,
+    pub(crate) merges: Option<MergePolicy>,
+    pub(crate) trailers: Option<TrailerMode>,
+    pub(crate) pair_trailers: Option<Vec<String>>,
+    pub(crate) reviews: Option<bool>,
+    pub(crate) review_trailers: Option<Vec<String>>,
+    pub(crate) mob: Option<bool>,
+    pub(crate) weight: Option<Weight>,
+    pub(crate) bucket: Option<Bucket>,
+    /// The roster file, relative to the repository root.
+    pub(crate) roster: Option<PathBuf>,
+    pub(crate) teams: Option<Vec<String>>,
+    pub(crate) members_only: Option<bool>,
+    pub(crate) identity: Option<Identity>,
+    pub(crate) normalize: Option<Vec<Normalization>>,
+    pub(crate) mailmap: Option<bool>,
+    pub(crate) format: Option<Format>,
+}
+
+impl Config {
+    /// Loads the user configuration and the configuration in the repository root, if present.
+    /// Settings from the repository take precedence over the user settings.
+    pub(crate) fn load(workdir: Option<&Path>) -> Result<Self> {
+        let user = match user_config_file() {
+            Some(path) => Self::read(&path)?,
+            None => None,
+        };
+        let repository = match workdir {
+            Some(workdir) => Self::read(&workdir.join(REPOSITORY_CONFIG))?,
+            None => None,
+        };
+
+        let config = match (repository, user) {
+            (Some(repository), Some(user)) => repository.or(user),
+            (Some(config), None) | (None, Some(config)) => config,
+            (None, None) => Config::default(),
+        };
+        Ok(config)
+    }
+
+    fn read(path: &Path) -> Result<Option<Self>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(eyre!("Could not read `{}`: {}", path.display(), err)),
+        };
+        let config = toml::from_str(&content)
+            .map_err(|err| eyre!("Invalid config file `{}`: {}", path.display(), err))?;
+        Ok(Some(config))
+    }
+
+    /// Combines both configs, values from `self` take precedence over the ones from `fallback`.
+    fn or(mut self, fallback: Self) -> Self {
+        for (name, replacement) in fallback.replacements {
+            let _ = self.replacements.entry(name).or_insert(replacement);
+        }
+
+        Config {
+            replacements: self.replacements,
+            range: self.range.or(fallback.range),
+            since: self.since.or(fallback.since),
+            until: self.until.or(fallback.until),
+            date_field: self.date_field.or(fallback.date_field),
+            merges: self.merges.or(fallback.merges),
+            trailers: self.trailers.or(fallback.trailers),
+            pair_trailers: self.pair_trailers.or(fallback.pair_trailers),
+            reviews: self.reviews.or(fallback.reviews),
+            review_trailers: self.review_trailers.or(fallback.review_trailers),
+            mob: self.mob.or(fallback.mob),
+            weight: self.weight.or(fallback.weight),
+            bucket: self.bucket.or(fallback.bucket),
+            roster: self.roster.or(fallback.roster),
+            teams: self.teams.or(fallback.teams),
+            members_only: self.members_only.or(fallback.members_only),
+            identity: self.identity.or(fallback.identity),
+            normalize: self.normalize.or(fallback.normalize),
+            mailmap: self.mailmap.or(fallback.mailmap),
+            format: self.format.or(fallback.format),
+        }
+    }
+
+    pub(crate) fn since(&self) -> Result<Option<i64>> {
+        Ok(self.since.as_deref().map(date::parse_since).transpose()?)
+    }
+
+    pub(crate) fn until(&self) -> Result<Option<i64>> {
+        Ok(self.until.as_deref().map(date::parse_until).transpose()?)
+    }
+}
+
+fn user_config_file() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_home.join(APPLICATION).join(USER_CONFIG))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config: Config = toml::from_str(
+            r#"
+            range = "v1.0.0..HEAD"
+            merges = "include"
+            trailers = "lenient"
+            identity = "email"
+            normalize = ["none"]
+            date-field = "author"
+            format = "csv"
+
+            [replacements]
+            "bob" = "Bob"
+            "alice" = "Alice"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.range.as_deref(), Some("v1.0.0..HEAD"));
+        assert_eq!(config.merges, Some(MergePolicy::Include));
+        assert_eq!(config.trailers, Some(TrailerMode::Lenient));
+        assert_eq!(config.identity, Some(Identity::Email));
+        assert_eq!(config.normalize, Some(vec![Normalization::None]));
+        assert_eq!(config.date_field, Some(DateField::Author));
+        assert_eq!(config.format, Some(Format::Csv));
+        assert_eq!(
+            config.replacements.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("bob".to_string(), "Bob".to_string()),
+                ("alice".to_string(), "Alice".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_key() {
+        assert!(toml::from_str::<Config>("rnage = \"HEAD~5..HEAD\"").is_err());
+    }
+
+    #[test]
+    fn test_precedence() {
+        let repository: Config = toml::from_str(
+            r#"
+            merges = "only"
+            [replacements]
+            "alice" = "Alice Keys"
+            "#,
+        )
+        .unwrap();
+        let user: Config = toml::from_str(
+            r#"
+            merges = "include"
+            identity = "email"
+            [replacements]
+            "alice" = "Alice"
+            "bob" = "Bob"
+            "#,
+        )
+        .unwrap();
+
+        let config = repository.or(user);
+        assert_eq!(config.merges, Some(MergePolicy::Only));
+        assert_eq!(config.identity, Some(Identity::Email));
+        assert_eq!(config.replacements["alice"], "Alice Keys");
+        assert_eq!(config.replacements["bob"], "Bob");
+    }
+}
@@ -0,0 +1,48 @@
+use super::Graph;
+use std::io::{self, Write};
+
+/// The width of the node of the most active author, in inches.
+const MAX_NODE_WIDTH: f64 = 2.0;
+/// The width of the heaviest edge, in points.
+const MAX_PEN_WIDTH: f64 = 5.0;
+
+pub(super) fn write(out: &mut impl Write, graph: &Graph<'_>) -> io::Result<()> {
+    let (kind, edge_op) = if graph.directed {
+        ("digraph", "->")
+    } else {
+        ("graph", "--")
+    };
+    writeln!(out, "{} pairing {{", kind)?;
+    writeln!(out, "  node [shape=circle, fixedsize=true];")?;
+
+    let max_commits = f64::from(graph.max_commits());
+    for (index, node) in graph.nodes.iter().enumerate() {
+        // the node of an author with a single commit is half as wide as the largest one
+        let width = MAX_NODE_WIDTH * (1.0 + f64::from(node.commits) / max_commits) / 2.0;
+        writeln!(
+            out,
+            "  n{} [label=\"{}\\n{}\", width={:.2}];",
+            index,
+            escape(node.name),
+            node.commits,
+            width
+        )?;
+    }
+
+    let max_weight = graph.max_weight() as f64;
+    for edge in &graph.edges {
+        let pen_width = 1.0 + (MAX_PEN_WIDTH - 1.0) * edge.weight as f64 / max_weight;
+        writeln!(
+            out,
+            "  n{} {} n{} [label=\"{}\", weight={}, penwidth={:.2}];",
+            edge.source, edge_op, edge.target, edge.weight, edge.weight, pen_width
+        )?;
+    }
+
+    writeln!(out, "}}")
+}
+
+/// Escapes a DOT string literal.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
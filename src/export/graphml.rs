@@ -0,0 +1,67 @@
+use super::Graph;
+use std::io::{self, Write};
+
+pub(super) fn write(out: &mut impl Write, graph: &Graph<'_>) -> io::Result<()> {
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="name" for="node" attr.name="name" attr.type="string"/>"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="commits" for="node" attr.name="commits" attr.type="int"/>"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="weight" for="edge" attr.name="weight" attr.type="long"/>"#
+    )?;
+
+    let edge_default = if graph.directed {
+        "directed"
+    } else {
+        "undirected"
+    };
+    writeln!(
+        out,
+        r#"  <graph id="pairing" edgedefault="{}">"#,
+        edge_default
+    )?;
+
+    for (index, node) in graph.nodes.iter().enumerate() {
+        writeln!(out, r#"    <node id="n{}">"#, index)?;
+        writeln!(
+            out,
+            r#"      <data key="name">{}</data>"#,
+            escape(node.name)
+        )?;
+        writeln!(out, r#"      <data key="commits">{}</data>"#, node.commits)?;
+        writeln!(out, "    </node>")?;
+    }
+
+    for edge in &graph.edges {
+        writeln!(
+            out,
+            r#"    <edge source="n{}" target="n{}">"#,
+            edge.source, edge.target
+        )?;
+        writeln!(out, r#"      <data key="weight">{}</data>"#, edge.weight)?;
+        writeln!(out, "    </edge>")?;
+    }
+
+    writeln!(out, "  </graph>")?;
+    writeln!(out, "</graphml>")
+}
+
+/// Escapes the characters that are not allowed in XML text and attribute values.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
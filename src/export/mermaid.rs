@@ -0,0 +1,63 @@
+use super::Graph;
+use std::io::{self, Write};
+
+/// The width of the heaviest edge, in pixels.
+const MAX_STROKE_WIDTH: u64 = 5;
+
+/// Writes a flowchart with round nodes. Mermaid has no node sizes,
+/// so the commits of each author are part of their label instead.
+pub(super) fn write(out: &mut impl Write, graph: &Graph<'_>) -> io::Result<()> {
+    writeln!(out, "flowchart LR")?;
+
+    for (index, node) in graph.nodes.iter().enumerate() {
+        writeln!(
+            out,
+            "  n{}((\"{}<br>{}\"))",
+            index,
+            escape(node.name),
+            node.commits
+        )?;
+    }
+
+    let edge_op = if graph.directed { "-->" } else { "---" };
+    for edge in &graph.edges {
+        writeln!(
+            out,
+            "  n{} {}|{}| n{}",
+            edge.source, edge_op, edge.weight, edge.target
+        )?;
+    }
+
+    let max_weight = graph.max_weight();
+    for (index, edge) in graph.edges.iter().enumerate() {
+        let stroke_width = 1 + (MAX_STROKE_WIDTH - 1) * edge.weight / max_weight;
+        writeln!(out, "  linkStyle {} stroke-width:{}px", index, stroke_width)?;
+    }
+
+    Ok(())
+}
+
+/// Escapes the characters that end a quoted label or are markup, as Mermaid renders labels as HTML,
+/// using Mermaid's entity codes.
+fn escape(value: &str) -> String {
+    value
+        .replace('#', "#35;")
+        .replace('"', "#quot;")
+        .replace('&', "#amp;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("Bob <3"), "Bob #lt;3");
+        assert_eq!(
+            escape("<b>Eve</b> & #1"),
+            "#lt;b#gt;Eve#lt;/b#gt; #amp; #35;1"
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use crate::{AuthorCounts, Relationship, Repo, Result, ScanOptions, StringCache, Weight};
+use clap::ValueEnum;
+use itertools::Itertools;
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+mod dot;
+mod graphml;
+mod mermaid;
+
+/// The output format of an exported pairing graph.
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub(crate) enum Format {
+    /// Graphviz DOT, e.g. for `dot -Tsvg`
+    #[default]
+    Dot,
+    /// GraphML, e.g. for Gephi or yEd
+    Graphml,
+    /// Mermaid flowchart, e.g. for Markdown documents
+    Mermaid,
+}
+
+/// Which edges are exported.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct EdgeOptions {
+    /// Export an edge from the driver to the navigator instead of one edge per pair.
+    pub(crate) directed: bool,
+    /// Drop edges with a lower weight.
+    pub(crate) min_weight: u64,
+    /// How the edges are weighted.
+    pub(crate) weight: Weight,
+}
+
+pub(crate) fn print_graph(
+    mut repo: Repo,
+    options: &ScanOptions,
+    format: Format,
+    edge_options: EdgeOptions,
+) -> Result<()> {
+    let counts = repo.extract_coauthors(options)?;
+    let graph = graph(counts, repo.string_cache(), edge_options);
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    write(&mut out, &graph, format)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+fn write(out: &mut impl Write, graph: &Graph<'_>, format: Format) -> io::Result<()> {
+    match format {
+        Format::Dot => dot::write(out, graph),
+        Format::Graphml => graphml::write(out, graph),
+        Format::Mermaid => mermaid::write(out, graph),
+    }
+}
+
+struct Graph<'a> {
+    directed: bool,
+    nodes: Vec<Node<'a>>,
+    edges: Vec<Edge>,
+}
+
+impl Graph<'_> {
+    fn max_commits(&self) -> u32 {
+        self.nodes
+            .iter()
+            .map(|node| node.commits)
+            .max()
+            .unwrap_or_default()
+            .max(1)
+    }
+
+    fn max_weight(&self) -> u64 {
+        self.edges
+            .iter()
+            .map(|edge| edge.weight)
+            .max()
+            .unwrap_or_default()
+            .max(1)
+    }
+}
+
+/// An author, sized by all of their commits.
+struct Node<'a> {
+    name: &'a str,
+    commits: u32,
+}
+
+/// Two authors that paired, by the index of their nodes.
+struct Edge {
+    source: usize,
+    target: usize,
+    weight: u64,
+}
+
+/// Builds the graph of all authors that committed or co-authored, sorted by name.
+///
+/// Undirected edges are weighted by all commits of the pair, directed edges by the commits
+/// where the source was the driver.
+fn graph(counts: AuthorCounts, string_cache: &StringCache, options: EdgeOptions) -> Graph<'_> {
+    let counts = counts
+        .into_iter()
+        .filter(|(_, counts)| counts.commits() > 0)
+        .sorted_by(|lhs, rhs| string_cache[lhs.0].cmp(&string_cache[rhs.0]))
+        .collect_vec();
+    let index = counts
+        .iter()
+        .enumerate()
+        .map(|(index, (author, _))| (*author, index))
+        .collect::<HashMap<_, _>>();
+
+    let mut edges = Vec::new();
+    for (source, (_, pairing_counts)) in counts.iter().enumerate() {
+        for (co_author, paired_with) in pairing_counts.iter() {
+            let target = match index.get(&co_author) {
+                Some(&target) => target,
+                None => continue,
+            };
+            let weight = if options.directed {
+                paired_with.weighted_as_author_of(Relationship::Pairing, options.weight)
+            } else if source < target {
+                paired_with.weighted_total_of(Relationship::Pairing, options.weight)
+            } else {
+                // the same pair is seen from the other author
+                continue;
+            };
+            if weight > 0 && weight >= options.min_weight {
+                edges.push(Edge {
+                    source,
+                    target,
+                    weight,
+                });
+            }
+        }
+    }
+    edges.sort_by_key(|edge| (edge.source, edge.target));
+
+    Graph {
+        directed: options.directed,
+        nodes: counts
+            .iter()
+            .map(|(author, counts)| Node {
+                name: &string_cache[*author],
+                commits: counts.commits(),
+            })
+            .collect(),
+        edges,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git_stats_core::DiffStats;
+
+    fn render(format: Format, directed: bool, min_weight: u64) -> String {
+        let mut string_cache = StringCache::new();
+        let alice = string_cache.intern("Alice");
+        let bob = string_cache.intern("Bob \"the builder\"");
+        let charlie = string_cache.intern("Charlie & Co");
+
+        let mut counts = AuthorCounts::default();
+        counts.add_commit(alice, &[bob], DiffStats::default());
+        counts.add_commit(alice, &[bob], DiffStats::default());
+        counts.add_commit(bob, &[alice], DiffStats::default());
+        counts.add_commit(charlie, &[alice], DiffStats::default());
+        counts.add_commit(charlie, &[], DiffStats::default());
+
+        let options = EdgeOptions {
+            directed,
+            min_weight,
+            weight: Weight::Commits,
+        };
+        let graph = graph(counts, &string_cache, options);
+        let mut out = Vec::new();
+        write(&mut out, &graph, format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_dot() {
+        assert_eq!(
+            render(Format::Dot, false, 0),
+            r#"graph pairing {
+  node [shape=circle, fixedsize=true];
+  n0 [label="Alice\n4", width=2.00];
+  n1 [label="Bob \"the builder\"\n3", width=1.75];
+  n2 [label="Charlie & Co\n2", width=1.50];
+  n0 -- n1 [label="3", weight=3, penwidth=5.00];
+  n0 -- n2 [label="1", weight=1, penwidth=2.33];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_dot_directed() {
+        assert_eq!(
+            render(Format::Dot, true, 0),
+            r#"digraph pairing {
+  node [shape=circle, fixedsize=true];
+  n0 [label="Alice\n4", width=2.00];
+  n1 [label="Bob \"the builder\"\n3", width=1.75];
+  n2 [label="Charlie & Co\n2", width=1.50];
+  n0 -> n1 [label="2", weight=2, penwidth=5.00];
+  n1 -> n0 [label="1", weight=1, penwidth=3.00];
+  n2 -> n0 [label="1", weight=1, penwidth=3.00];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_min_weight() {
+        let dot = render(Format::Dot, false, 2);
+        assert!(dot.contains("n0 -- n1"));
+        assert!(!dot.contains("n0 -- n2"));
+        // authors without edges are still shown
+        assert!(dot.contains("n2 [label"));
+    }
+
+    #[test]
+    fn test_graphml() {
+        assert_eq!(
+            render(Format::Graphml, false, 2),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="name" for="node" attr.name="name" attr.type="string"/>
+  <key id="commits" for="node" attr.name="commits" attr.type="int"/>
+  <key id="weight" for="edge" attr.name="weight" attr.type="long"/>
+  <graph id="pairing" edgedefault="undirected">
+    <node id="n0">
+      <data key="name">Alice</data>
+      <data key="commits">4</data>
+    </node>
+    <node id="n1">
+      <data key="name">Bob &quot;the builder&quot;</data>
+      <data key="commits">3</data>
+    </node>
+    <node id="n2">
+      <data key="name">Charlie &amp; Co</data>
+      <data key="commits">2</data>
+    </node>
+    <edge source="n0" target="n1">
+      <data key="weight">3</data>
+    </edge>
+  </graph>
+</graphml>
+"#
+        );
+    }
+
+    #[test]
+    fn test_mermaid() {
+        assert_eq!(
+            render(Format::Mermaid, true, 0),
+            r#"flowchart LR
+  n0(("Alice<br>4"))
+  n1(("Bob #quot;the builder#quot;<br>3"))
+  n2(("Charlie #amp; Co<br>2"))
+  n0 -->|2| n1
+  n1 -->|1| n0
+  n2 -->|1| n0
+  linkStyle 0 stroke-width:5px
+  linkStyle 1 stroke-width:3px
+  linkStyle 2 stroke-width:3px
+"#
+        );
+    }
+}
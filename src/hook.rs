@@ -0,0 +1,107 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use git_stats_core::git2::{ErrorCode, Repository};
+
+use crate::{Repo, Result, ScanOptions, APPLICATION};
+
+const COMMIT_MSG: &str = "commit-msg";
+
+/// Checks the commit message in `file` and returns whether it can be committed.
+pub(crate) fn commit_msg(repo: Repo, options: &ScanOptions, file: &Path) -> Result<bool> {
+    let message = fs::read_to_string(file)
+        .map_err(|err| eyre!("Could not read `{}`: {}", file.display(), err))?;
+    let (name, mail) = repo.default_author()?;
+
+    let problems = repo.check_message(&message, &name, mail.as_deref(), options)?;
+    if problems.is_empty() {
+        return Ok(true);
+    }
+
+    eprintln!(
+        "{}: the co-author trailers of the commit message are invalid:",
+        APPLICATION
+    );
+    for problem in problems {
+        eprintln!("  - {}", problem);
+    }
+    eprintln!("Use `git commit --no-verify` to commit anyway.");
+    Ok(false)
+}
+
+/// Installs the `commit-msg` hook that runs `git-stats hook commit-msg`.
+pub(crate) fn install(repo: &Repo, force: bool) -> Result<()> {
+    let hook = hooks_dir(repo.repository())?.join(COMMIT_MSG);
+    if !force && hook.exists() {
+        bail!(
+            "`{}` already exists. Use `--force` to overwrite it.",
+            hook.display()
+        );
+    }
+
+    let script = format!(
+        "#!/bin/sh\n# Installed by `{0} install-hook`\nexec {0} hook {1} \"$1\"\n",
+        APPLICATION, COMMIT_MSG
+    );
+    if let Some(dir) = hook.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&hook, script)
+        .map_err(|err| eyre!("Could not write `{}`: {}", hook.display(), err))?;
+    make_executable(&hook)?;
+
+    println!("Installed `{}`", hook.display());
+    Ok(())
+}
+
+/// The directory of the hooks, which can be changed with `core.hooksPath`.
+/// Linked worktrees share the hooks of the main repository.
+fn hooks_dir(repository: &Repository) -> Result<PathBuf> {
+    let hooks_path = match repository.config()?.get_path("core.hooksPath") {
+        Ok(path) => Some(path),
+        Err(err) if err.code() == ErrorCode::NotFound => None,
+        Err(err) => return Err(err.into()),
+    };
+    let dir = match hooks_path {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => repository
+            .workdir()
+            .unwrap_or_else(|| repository.path())
+            .join(path),
+        None => common_dir(repository)?.join("hooks"),
+    };
+    Ok(dir)
+}
+
+/// The git directory that is shared by all worktrees, which a linked worktree
+/// points to from the `commondir` file in its own git directory.
+fn common_dir(repository: &Repository) -> Result<PathBuf> {
+    let git_dir = repository.path();
+    if !repository.is_worktree() {
+        return Ok(git_dir.to_path_buf());
+    }
+    let file = git_dir.join("commondir");
+    let common_dir = fs::read_to_string(&file)
+        .map_err(|err| eyre!("Could not read `{}`: {}", file.display(), err))?;
+    let common_dir = git_dir.join(common_dir.trim_end());
+    common_dir
+        .canonicalize()
+        .map_err(|err| eyre!("Could not find `{}`: {}", common_dir.display(), err))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_mode(permissions.mode() | 0o755);
+    fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
@@ -1,38 +1,250 @@
 #[macro_use]
-extern crate maplit;
-#[macro_use]
 extern crate eyre;
 
 use std::path::PathBuf;
 
 use crate::{
-    author_counts::{AuthorCounts, PairingCounts},
-    repo::{Repo, HAN_SOLO},
-    stringcache::StringCache,
+    config::Config,
+    report::{Format, View},
 };
-use clap::{AppSettings, Parser};
+use clap::{AppSettings, Parser, Subcommand};
+use color_eyre::Section;
 use eyre::Result;
+use git_stats_core::{
+    date, Activity, AuthorCounts, Bucket, DateField, Identity, IdentityOptions, KnownAuthor,
+    Member, MergePolicy, Normalization, PairingCounts, Relationship, Repo, Roster, ScanOptions,
+    StringCache, TrailerMode, Weight, DEFAULT_PAIR_TRAILERS, DEFAULT_REVIEW_TRAILERS,
+};
 
-mod author_counts;
-mod repo;
-mod stringcache;
+mod config;
+mod export;
+mod hook;
+mod pair;
+mod report;
+mod roster;
+mod suggest;
 mod ui;
 
+static APPLICATION: &str = env!("CARGO_PKG_NAME");
+
 #[derive(Parser, Debug)]
-#[clap(version, author, about, global_setting = AppSettings::ColoredHelp)]
+#[clap(
+    version,
+    author,
+    about,
+    global_setting = AppSettings::ColoredHelp,
+    after_help = "Defaults can be set in `.git-stats.toml` in the repository root \
+                  or in `$XDG_CONFIG_HOME/git-stats/config.toml`. \
+                  Options given on the command line take precedence."
+)]
 struct Opts {
     /// Path to Git repository
-    #[clap(short, long)]
+    #[clap(short, long, global = true)]
     repository: Option<PathBuf>,
     /// Replace authors based on this map. Can be specified multiple times, value are delimited by `=`
-    #[clap(short = 'R', long="replacement", parse(try_from_str = parse_key_val), number_of_values = 1)]
+    ///
+    /// The name has to match exactly, unless it is prefixed with `i:` to ignore case,
+    /// `glob:` for a case insensitive glob with `*` and `?` wildcards,
+    /// or `re:` for a regular expression. Wildcards and capture groups can be used in the replacement
+    /// as `$1`, `$2`, etc., e.g. `-R 're:(\w+)\.? Smith=$1 Smith'`.
+    #[clap(short = 'R', long="replacement", parse(try_from_str = parse_key_val), number_of_values = 1, global = true)]
     replacements: Vec<(String, String)>,
+    /// How to tell whether two commits were authored by the same person.
+    ///
+    /// With `email`, authors and co-authors are identified by their email address,
+    /// so that changes to or typos in the display name do not split a person in two.
+    /// The replacements only apply to names, use the mailmap to merge email addresses.
+    ///
+    /// [default: name]
+    #[clap(long, value_enum, global = true)]
+    identity: Option<Identity>,
+    /// How names are normalized after the replacements have been applied.
+    ///
+    /// Takes a comma-separated list of steps that are applied in order.
+    /// Use `none` to display names verbatim,
+    /// or add `strip-diacritics` to also merge names like `José` and `Jose`.
+    ///
+    /// [default: nfc,umlauts,collapse-whitespace]
+    #[clap(long, value_enum, use_value_delimiter = true, global = true)]
+    normalize: Option<Vec<Normalization>>,
+    /// Resolve authors through the repository's mailmap, even if the config turns it off.
+    ///
+    /// By default, authors and co-authors are mapped according to `.mailmap`,
+    /// `mailmap.file` and `mailmap.blob` before any replacement is applied.
+    #[clap(long, overrides_with = "no_mailmap", global = true)]
+    mailmap: bool,
+    /// Do not resolve authors through the repository's mailmap
+    #[clap(long, overrides_with = "mailmap", global = true)]
+    no_mailmap: bool,
+    /// Print how every author is resolved through the mailmap and the replacements, then exit
+    #[clap(long, global = true)]
+    explain_identities: bool,
     /// Commit range to scan. Default is to go from HEAD to the very beginning.
     ///
     /// This accepts the form of `<commit-1>..<commit-2>` and will start scanning at `commit-2` and stop at `commit-1`.
     /// The default can be seen as if it was defined as `..HEAD`.
-    #[clap(long)]
+    #[clap(long, global = true)]
     range: Option<String>,
+    /// How to treat merge commits.
+    ///
+    /// Use `include` or `only` for workflows where the co-author trailers are added to merge commits,
+    /// e.g. when squash-merging pull requests.
+    ///
+    /// [default: skip]
+    #[clap(long, value_enum, global = true)]
+    merges: Option<MergePolicy>,
+    /// Only scan commits more recent than this date.
+    ///
+    /// Accepts ISO dates like `2022-10-01` or `2022-10-01 12:00:00`, which are interpreted as UTC,
+    /// as well as relative dates like `2 weeks ago`, `yesterday`, or `3.days.ago`.
+    #[clap(long, parse(try_from_str = date::parse_since), global = true)]
+    since: Option<i64>,
+    /// Only scan commits older than this date. Accepts the same formats as `--since`.
+    ///
+    /// A plain date like `2022-10-31` includes all commits of that day.
+    #[clap(long, parse(try_from_str = date::parse_until), global = true)]
+    until: Option<i64>,
+    /// Which commit date is used by `--since` and `--until`.
+    ///
+    /// [default: committer]
+    #[clap(long, value_enum, global = true)]
+    date_field: Option<DateField>,
+    /// Where co-author trailers are looked for in a commit message.
+    ///
+    /// With `strict`, only the trailer block at the end of the message is considered,
+    /// following the rules of `git interpret-trailers`, so that a `Co-authored-by` line quoted
+    /// in the body, e.g. of a revert, is not counted.
+    /// Use `lenient` to accept co-author lines anywhere in the message.
+    ///
+    /// [default: strict]
+    #[clap(long, value_enum, global = true)]
+    trailers: Option<TrailerMode>,
+    /// Trailer keys that name a navigator, e.g. `Co-authored-by,Paired-with`. Case insensitive.
+    ///
+    /// [default: Co-authored-by]
+    #[clap(long, use_value_delimiter = true, global = true)]
+    pair_trailers: Option<Vec<String>>,
+    /// Also count reviews, which the UI shows as a separate graph
+    #[clap(long, global = true)]
+    reviews: bool,
+    /// Trailer keys that name a reviewer when `--reviews` is given. Case insensitive.
+    ///
+    /// `Signed-off-by` is not a review by default, as authors sign off their own commits.
+    ///
+    /// [default: Reviewed-by,Helped-by]
+    #[clap(long, use_value_delimiter = true, global = true)]
+    review_trailers: Option<Vec<String>>,
+    /// Count all co-authors of a commit as pairing with each other, not only with the author
+    #[clap(long, global = true)]
+    mob: bool,
+    /// How the commits of a pair are weighted. `lines` and `files` compare each commit
+    /// to its first parent, which is slow for large histories.
+    ///
+    /// Merge commits that are counted with `--merges` are compared to their first parent as well,
+    /// so they count all changes of the merged branch, which its own commits already counted.
+    ///
+    /// [default: commits]
+    #[clap(long, value_enum, global = true)]
+    weight: Option<Weight>,
+    /// Group the solo and paired commits of each author by time, for the timeline in the UI
+    /// and the `timeline` report, which defaults to weeks
+    #[clap(long, value_enum, global = true)]
+    bucket: Option<Bucket>,
+    /// TOML file that lists the members of the team with their emails, aliases, team, and active dates.
+    ///
+    /// Authors on the roster are displayed by their name on the roster,
+    /// and co-authors that are not on the roster are flagged by `lint` and the commit hook.
+    #[clap(long, global = true)]
+    roster: Option<PathBuf>,
+    /// Only count members of the roster who were active in the scanned range
+    #[clap(long, global = true)]
+    members_only: bool,
+    /// Only count members of these teams from the roster, e.g. `--team platform,apps`
+    #[clap(long = "team", use_value_delimiter = true, global = true)]
+    teams: Option<Vec<String>>,
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print the pairing statistics to stdout instead of starting the interactive UI
+    Report {
+        /// Output format of the report
+        ///
+        /// [default: table]
+        #[clap(long, value_enum)]
+        format: Option<Format>,
+        /// What to list, the pairs of authors or the solo and paired commits of each author
+        #[clap(long, value_enum, default_value_t)]
+        view: View,
+        /// Write the matrix view as a self-contained HTML page with a heatmap, instead of `--view`
+        #[clap(long, conflicts_with_all = &["format", "view"])]
+        html: bool,
+    },
+    /// Print the pairing graph of all authors, e.g. to render it with Graphviz or Mermaid
+    Export {
+        /// Output format of the graph
+        #[clap(long, value_enum, default_value_t)]
+        format: export::Format,
+        /// Draw an edge from the driver to the navigator, weighted by the commits of the driver,
+        /// instead of one edge per pair
+        #[clap(long)]
+        directed: bool,
+        /// Drop edges with a lower weight, see `--weight`
+        #[clap(long, default_value_t = 1)]
+        min_weight: u64,
+    },
+    /// Suggest who should pair next, preferring authors that worked together the least.
+    ///
+    /// Use `--since` to only look at recent commits, e.g. `--since '4 weeks ago'`.
+    Suggest {
+        /// The authors to pair up, by the names shown in the reports or their roster aliases.
+        /// Defaults to the selected roster members with `--members-only` or `--team`,
+        /// and otherwise to all authors with commits in the scanned range.
+        authors: Vec<String>,
+        /// Let older commits count less, by halving their weight after this many days
+        #[clap(long)]
+        half_life: Option<f64>,
+        /// Output format of the suggestions
+        ///
+        /// [default: table]
+        #[clap(long, value_enum)]
+        format: Option<Format>,
+    },
+    /// List trailers that name a co-author or reviewer, but cannot be parsed,
+    /// and co-authors that are not on the roster if `--roster` is given.
+    ///
+    /// Exits with a non-zero status if any are found, e.g. to be used as a CI check.
+    Lint,
+    /// Run a Git hook, see `install-hook`
+    Hook {
+        #[clap(subcommand)]
+        hook: Hook,
+    },
+    /// Install a `commit-msg` hook that rejects invalid co-author trailers.
+    ///
+    /// The hook rejects malformed trailers, co-authors that never appeared in the history,
+    /// and authors that name themselves as co-author.
+    InstallHook {
+        /// Overwrite an existing hook
+        #[clap(long)]
+        force: bool,
+    },
+    /// Pick co-authors from the history and add them as trailers to a commit message.
+    ///
+    /// Each co-author is added with the email address of their most recent commit.
+    /// Can be used in a `prepare-commit-msg` hook, e.g. `git-stats pair "$1" < /dev/tty`.
+    Pair {
+        /// The commit message to add the trailers to. Defaults to the file from `commit.template`.
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum Hook {
+    /// Check the co-author trailers of the commit message in the given file
+    CommitMsg { file: PathBuf },
 }
 
 /// Parse a replacement key-value pair
@@ -43,6 +255,20 @@ fn parse_key_val(s: &str) -> Result<(String, String)> {
     Ok((s[..pos].into(), s[pos + 1..].into()))
 }
 
+fn to_strings(keys: &[&str]) -> Vec<String> {
+    keys.iter().map(|&key| key.to_string()).collect()
+}
+
+/// The value of a pair of flags like `--mailmap` and `--no-mailmap`, if any of them is given.
+/// The flags override each other, so only the last one given is set.
+fn flag(on: bool, off: bool) -> Option<bool> {
+    match (on, off) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
     let opts: Opts = Opts::parse();
@@ -50,9 +276,145 @@ fn main() -> Result<()> {
     let Opts {
         repository,
         replacements,
+        identity,
+        normalize,
+        mailmap,
+        no_mailmap,
+        explain_identities,
         range,
+        merges,
+        since,
+        until,
+        date_field,
+        trailers,
+        pair_trailers,
+        reviews,
+        review_trailers,
+        mob,
+        weight,
+        bucket,
+        roster,
+        members_only,
+        teams,
+        command,
     } = opts;
 
-    let repo = Repo::open(repository, replacements)?;
-    ui::render_coauthors(repo, range)
+    let repository = Repo::discover(repository).suggestion(Suggestions::NotInGitRepository)?;
+    let config = Config::load(repository.workdir())?;
+
+    let weight = weight.or(config.weight).unwrap_or_default();
+    let options = ScanOptions {
+        range: range.or_else(|| config.range.clone()),
+        merges: merges.or(config.merges).unwrap_or_default(),
+        since: match since {
+            Some(since) => Some(since),
+            None => config.since()?,
+        },
+        until: match until {
+            Some(until) => Some(until),
+            None => config.until()?,
+        },
+        date_field: date_field.or(config.date_field).unwrap_or_default(),
+        trailers: trailers.or(config.trailers).unwrap_or_default(),
+        pair_trailers: pair_trailers
+            .or_else(|| config.pair_trailers.clone())
+            .unwrap_or_else(|| to_strings(&DEFAULT_PAIR_TRAILERS)),
+        review_trailers: if reviews || config.reviews.unwrap_or_default() {
+            review_trailers
+                .or_else(|| config.review_trailers.clone())
+                .unwrap_or_else(|| to_strings(&DEFAULT_REVIEW_TRAILERS))
+        } else {
+            Vec::new()
+        },
+        mob: mob || config.mob.unwrap_or_default(),
+        diff_stats: weight != Weight::Commits,
+        bucket: bucket.or(config.bucket),
+        members_only: members_only || config.members_only.unwrap_or_default(),
+        teams: teams.or_else(|| config.teams.clone()).unwrap_or_default(),
+    };
+
+    // a roster from the config is relative to the repository root
+    let roster = match (roster, &config.roster) {
+        (Some(path), _) => roster::load(&path)?,
+        (None, Some(path)) => {
+            let root = repository.workdir().unwrap_or_else(|| repository.path());
+            roster::load(&root.join(path))?
+        }
+        (None, None) => Roster::default(),
+    };
+    if options.filters_members() && roster.is_empty() {
+        bail!("`--members-only` and `--team` require a roster, use `--roster`");
+    }
+
+    let identity = IdentityOptions {
+        // replacements are applied in order, so the ones from the command line come first
+        replacements: replacements
+            .into_iter()
+            .chain(config.replacements)
+            .collect(),
+        identity: identity.or(config.identity).unwrap_or_default(),
+        use_mailmap: flag(mailmap, no_mailmap).or(config.mailmap).unwrap_or(true),
+        normalization: normalize
+            .or(config.normalize)
+            .unwrap_or_else(|| Normalization::DEFAULT.to_vec()),
+        roster,
+    };
+
+    let repo = Repo::new(repository, identity)?;
+    if explain_identities {
+        return report::print_identities(&repo, &options);
+    }
+
+    match command {
+        Some(Command::Report { html: true, .. }) => {
+            report::print_matrix_html(repo, &options, weight)
+        }
+        Some(Command::Report { format, view, .. }) => {
+            let format = format.or(config.format).unwrap_or(Format::Table);
+            report::print_report(repo, &options, format, view, weight)
+        }
+        Some(Command::Export {
+            format,
+            directed,
+            min_weight,
+        }) => {
+            let edge_options = export::EdgeOptions {
+                directed,
+                min_weight,
+                weight,
+            };
+            export::print_graph(repo, &options, format, edge_options)
+        }
+        Some(Command::Suggest {
+            authors,
+            half_life,
+            format,
+        }) => {
+            let format = format.or(config.format).unwrap_or(Format::Table);
+            report::print_suggestions(repo, &options, format, &authors, weight, half_life)
+        }
+        Some(Command::Hook {
+            hook: Hook::CommitMsg { file },
+        }) => {
+            if !hook::commit_msg(repo, &options, &file)? {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Some(Command::InstallHook { force }) => hook::install(&repo, force),
+        Some(Command::Pair { file }) => pair::pair(&repo, &options, file),
+        Some(Command::Lint) => {
+            if !report::print_lint(&repo, &options)? {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        None => ui::render_coauthors(repo, options, weight),
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum Suggestions {
+    #[error("Try running {} from within a Git repository.", APPLICATION)]
+    NotInGitRepository,
 }
@@ -0,0 +1,76 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use co_authors::{append_trailers, Trailer, CO_AUTHORED_BY};
+use git_stats_core::git2::ErrorCode;
+
+use crate::{ui, Repo, Result, ScanOptions};
+
+/// Lets the user pick co-authors from the history and adds them as trailers to the commit message
+/// in `file`, or to the file from `commit.template` if no file is given.
+pub(crate) fn pair(repo: &Repo, options: &ScanOptions, file: Option<PathBuf>) -> Result<()> {
+    let file = match file {
+        Some(file) => file,
+        None => commit_template(repo)?,
+    };
+
+    let author = repo
+        .default_author()
+        .ok()
+        .map(|(name, mail)| repo.identity_key(&name, mail.as_deref()));
+    let authors = repo
+        .known_authors(options)?
+        .into_iter()
+        // a co-author without email is not a valid trailer
+        .filter(|known| known.mail.is_some() && Some(&known.key) != author.as_ref())
+        .collect();
+
+    let picked = ui::pick_co_authors(authors)?;
+    if picked.is_empty() {
+        return Ok(());
+    }
+
+    let values = picked
+        .iter()
+        .map(|known| {
+            format!(
+                "{} <{}>",
+                known.name,
+                known.mail.as_deref().unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>();
+    let trailers = values
+        .iter()
+        .map(|value| Trailer {
+            key: CO_AUTHORED_BY,
+            value,
+        })
+        .collect::<Vec<_>>();
+
+    let message = read_message(&file)?;
+    fs::write(&file, append_trailers(&message, &trailers))
+        .map_err(|err| eyre!("Could not write `{}`: {}", file.display(), err))?;
+
+    Ok(())
+}
+
+fn read_message(file: &Path) -> Result<String> {
+    match fs::read_to_string(file) {
+        Ok(message) => Ok(message),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(err) => Err(eyre!("Could not read `{}`: {}", file.display(), err)),
+    }
+}
+
+fn commit_template(repo: &Repo) -> Result<PathBuf> {
+    match repo.repository().config()?.get_path("commit.template") {
+        Ok(path) => Ok(path),
+        Err(err) if err.code() == ErrorCode::NotFound => {
+            bail!("No file given and `commit.template` is not configured.")
+        }
+        Err(err) => Err(err.into()),
+    }
+}
@@ -0,0 +1,170 @@
+use super::{AuthorRow, Matrix, MobSizeRow, Row, TimelineRow};
+use crate::{date, suggest::Suggestion};
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+};
+
+const HEADER: [&str; 4] = ["author", "co_author", "as_driver", "total"];
+const TEAMS_HEADER: [&str; 4] = ["team", "co_team", "as_driver", "total"];
+const AUTHORS_HEADER: [&str; 4] = ["author", "solo", "paired", "pairing_ratio"];
+const MOB_SIZES_HEADER: [&str; 3] = ["author", "size", "commits"];
+const TIMELINE_HEADER: [&str; 4] = ["author", "period", "solo", "paired"];
+const SUGGESTIONS_HEADER: [&str; 5] = ["author", "co_author", "together", "last_paired", "score"];
+
+/// Writes the rows as delimiter separated values, e.g. CSV or TSV.
+pub(super) fn write(out: &mut impl Write, rows: &[Row<'_>], delimiter: u8) -> io::Result<()> {
+    write_pairs(out, &HEADER, rows, delimiter)
+}
+
+/// Writes one record per pair of teams as delimiter separated values.
+pub(super) fn write_teams(out: &mut impl Write, rows: &[Row<'_>], delimiter: u8) -> io::Result<()> {
+    write_pairs(out, &TEAMS_HEADER, rows, delimiter)
+}
+
+fn write_pairs(
+    out: &mut impl Write,
+    header: &[&str],
+    rows: &[Row<'_>],
+    delimiter: u8,
+) -> io::Result<()> {
+    write_record(out, header, delimiter)?;
+    for row in rows {
+        let record = [
+            row.author.to_string(),
+            row.co_author.to_string(),
+            row.as_driver.to_string(),
+            row.total.to_string(),
+        ];
+        write_record(out, &record, delimiter)?;
+    }
+    Ok(())
+}
+
+/// Writes one record per author as delimiter separated values.
+pub(super) fn write_authors(
+    out: &mut impl Write,
+    rows: &[AuthorRow<'_>],
+    delimiter: u8,
+) -> io::Result<()> {
+    write_record(out, &AUTHORS_HEADER, delimiter)?;
+    for row in rows {
+        let record = [
+            row.author.to_string(),
+            row.solo.to_string(),
+            row.paired.to_string(),
+            format!("{:.3}", row.pairing_ratio),
+        ];
+        write_record(out, &record, delimiter)?;
+    }
+    Ok(())
+}
+
+/// Writes one record per author and group size as delimiter separated values.
+pub(super) fn write_mob_sizes(
+    out: &mut impl Write,
+    rows: &[MobSizeRow<'_>],
+    delimiter: u8,
+) -> io::Result<()> {
+    write_record(out, &MOB_SIZES_HEADER, delimiter)?;
+    for row in rows {
+        let record = [
+            row.author.to_string(),
+            row.size.to_string(),
+            row.commits.to_string(),
+        ];
+        write_record(out, &record, delimiter)?;
+    }
+    Ok(())
+}
+
+/// Writes one record per author and period as delimiter separated values.
+pub(super) fn write_timeline(
+    out: &mut impl Write,
+    rows: &[TimelineRow<'_>],
+    delimiter: u8,
+) -> io::Result<()> {
+    write_record(out, &TIMELINE_HEADER, delimiter)?;
+    for row in rows {
+        let record = [
+            row.author.to_string(),
+            row.period.clone(),
+            row.solo.to_string(),
+            row.paired.to_string(),
+        ];
+        write_record(out, &record, delimiter)?;
+    }
+    Ok(())
+}
+
+/// Writes one record per suggested pair as delimiter separated values.
+/// The fields of the co-author are empty for an author without a partner.
+pub(super) fn write_suggestions(
+    out: &mut impl Write,
+    suggestions: &[Suggestion<'_>],
+    delimiter: u8,
+) -> io::Result<()> {
+    write_record(out, &SUGGESTIONS_HEADER, delimiter)?;
+    for suggestion in suggestions {
+        let record = [
+            suggestion.author.to_string(),
+            suggestion.co_author.unwrap_or_default().to_string(),
+            suggestion.together.to_string(),
+            suggestion.last_paired.map(date::format).unwrap_or_default(),
+            format!("{:.3}", suggestion.score),
+        ];
+        write_record(out, &record, delimiter)?;
+    }
+    Ok(())
+}
+
+/// Writes one record per driver with one field per navigator as delimiter separated values.
+/// The fields of the drivers themselves are empty.
+pub(super) fn write_matrix(
+    out: &mut impl Write,
+    matrix: &Matrix<'_>,
+    delimiter: u8,
+) -> io::Result<()> {
+    let header = std::iter::once("driver")
+        .chain(matrix.authors.iter().copied())
+        .collect::<Vec<_>>();
+    write_record(out, &header, delimiter)?;
+    for (driver, author) in matrix.authors.iter().enumerate() {
+        let record = std::iter::once(author.to_string())
+            .chain((0..matrix.authors.len()).map(|navigator| {
+                if driver == navigator {
+                    String::new()
+                } else {
+                    matrix.as_driver[driver][navigator].to_string()
+                }
+            }))
+            .collect::<Vec<_>>();
+        write_record(out, &record, delimiter)?;
+    }
+    Ok(())
+}
+
+fn write_record(out: &mut impl Write, fields: &[impl AsRef<str>], delimiter: u8) -> io::Result<()> {
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            out.write_all(&[delimiter])?;
+        }
+        out.write_all(escape(field.as_ref(), delimiter).as_bytes())?;
+    }
+    out.write_all(b"\n")
+}
+
+/// Quotes fields for CSV following RFC 4180.
+/// TSV has no quoting, so tabs and line breaks are replaced by spaces instead.
+fn escape(field: &str, delimiter: u8) -> Cow<'_, str> {
+    let needs_escaping = |c: char| c == char::from(delimiter) || matches!(c, '"' | '\n' | '\r');
+    if !field.contains(needs_escaping) {
+        return field.into();
+    }
+
+    if delimiter == b'\t' {
+        field.replace(['\t', '\n', '\r'], " ").into()
+    } else {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    }
+}
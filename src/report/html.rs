@@ -0,0 +1,101 @@
+use super::{Matrix, ASYMMETRY_RATIO};
+use crate::Weight;
+use std::io::{self, Write};
+
+const STYLE: [&str; 5] = [
+    "body { font-family: sans-serif; }",
+    "table { border-collapse: collapse; }",
+    "th, td { padding: 0.3em 0.6em; border: 1px solid #ddd; text-align: right; }",
+    "th[scope=row] { text-align: left; }",
+    "td.asymmetric { outline: 2px solid #d62728; outline-offset: -2px; font-weight: bold; }",
+];
+
+/// Writes a self-contained page with a heatmap of the matrix.
+/// The darker a cell, the more the driver of its row worked with the navigator of its column.
+pub(super) fn write_matrix(
+    out: &mut impl Write,
+    matrix: &Matrix<'_>,
+    weight: Weight,
+) -> io::Result<()> {
+    let unit = match weight {
+        Weight::Commits => "commits",
+        Weight::Lines => "changed lines",
+        Weight::Files => "changed files",
+    };
+
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, r#"<html lang="en">"#)?;
+    writeln!(out, "<head>")?;
+    writeln!(out, r#"  <meta charset="utf-8">"#)?;
+    writeln!(out, "  <title>Pairing matrix</title>")?;
+    writeln!(out, "  <style>")?;
+    for rule in STYLE {
+        writeln!(out, "    {}", rule)?;
+    }
+    writeln!(out, "  </style>")?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+    writeln!(out, "<table>")?;
+    writeln!(
+        out,
+        "  <caption>Pairing matrix of {}, by driver (rows) and navigator (columns)</caption>",
+        unit
+    )?;
+
+    write!(out, "  <tr><th>Driver \\ Navigator</th>")?;
+    for author in &matrix.authors {
+        write!(out, r#"<th scope="col">{}</th>"#, escape(author))?;
+    }
+    writeln!(out, "</tr>")?;
+
+    let max = matrix.max().max(1) as f64;
+    for (driver, author) in matrix.authors.iter().enumerate() {
+        write!(out, r#"  <tr><th scope="row">{}</th>"#, escape(author))?;
+        for (navigator, co_author) in matrix.authors.iter().enumerate() {
+            if driver == navigator {
+                write!(out, "<td>-</td>")?;
+                continue;
+            }
+
+            let as_driver = matrix.as_driver[driver][navigator];
+            let class = if matrix.asymmetric[driver][navigator] {
+                r#" class="asymmetric""#
+            } else {
+                ""
+            };
+            let alpha = as_driver as f64 / max;
+            write!(
+                out,
+                r#"<td{} style="background: rgba(31, 119, 180, {:.2})" title="{} drove {} and navigated {} of the {} with {}">{}</td>"#,
+                class,
+                alpha,
+                escape(author),
+                as_driver,
+                matrix.as_navigator(driver, navigator),
+                unit,
+                escape(co_author),
+                as_driver
+            )?;
+        }
+        writeln!(out, "</tr>")?;
+    }
+
+    writeln!(out, "</table>")?;
+    writeln!(
+        out,
+        "<p>Outlined cells mark drivers that drove at least {:.0}% of the time with this navigator, \
+         consider switching roles.</p>",
+        ASYMMETRY_RATIO * 100.0
+    )?;
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")
+}
+
+/// Escapes the characters that are not allowed in HTML text and attribute values.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
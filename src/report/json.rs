@@ -0,0 +1,272 @@
+use super::{AuthorRow, Matrix, MobSizeRow, Row, TimelineRow};
+use crate::{
+    date,
+    suggest::{Scoring, Suggestion},
+    Weight,
+};
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// Version of the JSON document layout.
+/// Must be incremented whenever a field is removed, renamed, or changes its meaning.
+///
+/// Version 2 removed the pairs with the placeholder for solo commits.
+const SCHEMA_VERSION: u32 = 2;
+
+#[derive(Serialize)]
+struct Report<'a> {
+    schema_version: u32,
+    /// What `as_driver` and `total` count, see [Weight].
+    weight: Weight,
+    pairs: Vec<Pair<'a>>,
+}
+
+#[derive(Serialize)]
+struct TeamsReport<'a> {
+    schema_version: u32,
+    /// What `as_driver` and `total` count, see [Weight].
+    weight: Weight,
+    teams: Vec<TeamPair<'a>>,
+}
+
+#[derive(Serialize)]
+struct TeamPair<'a> {
+    team: &'a str,
+    co_team: &'a str,
+    as_driver: u64,
+    total: u64,
+}
+
+#[derive(Serialize)]
+struct AuthorsReport<'a> {
+    schema_version: u32,
+    authors: Vec<Author<'a>>,
+}
+
+#[derive(Serialize)]
+struct Author<'a> {
+    author: &'a str,
+    solo: u32,
+    paired: u32,
+    pairing_ratio: f64,
+}
+
+#[derive(Serialize)]
+struct MobSizesReport<'a> {
+    schema_version: u32,
+    mob_sizes: Vec<MobSize<'a>>,
+}
+
+#[derive(Serialize)]
+struct MobSize<'a> {
+    author: &'a str,
+    size: usize,
+    commits: u32,
+}
+
+#[derive(Serialize)]
+struct TimelineReport<'a> {
+    schema_version: u32,
+    timeline: Vec<Period<'a>>,
+}
+
+#[derive(Serialize)]
+struct Period<'a> {
+    author: &'a str,
+    period: &'a str,
+    solo: u32,
+    paired: u32,
+}
+
+#[derive(Serialize)]
+struct MatrixReport<'a> {
+    schema_version: u32,
+    /// What `as_driver` counts, see [Weight].
+    weight: Weight,
+    authors: &'a [&'a str],
+    /// How often the author at the first index drove with the author at the second index.
+    as_driver: &'a [Vec<u64>],
+    asymmetric_pairs: Vec<AsymmetricPair<'a>>,
+}
+
+#[derive(Serialize)]
+struct AsymmetricPair<'a> {
+    driver: &'a str,
+    navigator: &'a str,
+    as_driver: u64,
+    as_navigator: u64,
+}
+
+#[derive(Serialize)]
+struct SuggestionsReport<'a> {
+    schema_version: u32,
+    /// What `together` counts, see [Weight].
+    weight: Weight,
+    /// The number of days after which the commits of a pair count half as much in the `score`.
+    half_life: Option<f64>,
+    suggestions: Vec<SuggestedPair<'a>>,
+}
+
+#[derive(Serialize)]
+struct SuggestedPair<'a> {
+    author: &'a str,
+    /// Missing for an author without a partner.
+    co_author: Option<&'a str>,
+    together: u64,
+    /// Formatted like `--since`, or missing if the pair never committed together.
+    last_paired: Option<String>,
+    score: f64,
+}
+
+#[derive(Serialize)]
+struct Pair<'a> {
+    author: &'a str,
+    co_author: &'a str,
+    as_driver: u64,
+    total: u64,
+}
+
+pub(super) fn write(out: &mut impl Write, rows: &[Row<'_>], weight: Weight) -> io::Result<()> {
+    let report = Report {
+        schema_version: SCHEMA_VERSION,
+        weight,
+        pairs: rows
+            .iter()
+            .map(|row| Pair {
+                author: row.author,
+                co_author: row.co_author,
+                as_driver: row.as_driver,
+                total: row.total,
+            })
+            .collect(),
+    };
+
+    serde_json::to_writer_pretty(&mut *out, &report)?;
+    writeln!(out)
+}
+
+pub(super) fn write_teams(
+    out: &mut impl Write,
+    rows: &[Row<'_>],
+    weight: Weight,
+) -> io::Result<()> {
+    let report = TeamsReport {
+        schema_version: SCHEMA_VERSION,
+        weight,
+        teams: rows
+            .iter()
+            .map(|row| TeamPair {
+                team: row.author,
+                co_team: row.co_author,
+                as_driver: row.as_driver,
+                total: row.total,
+            })
+            .collect(),
+    };
+
+    serde_json::to_writer_pretty(&mut *out, &report)?;
+    writeln!(out)
+}
+
+pub(super) fn write_authors(out: &mut impl Write, rows: &[AuthorRow<'_>]) -> io::Result<()> {
+    let report = AuthorsReport {
+        schema_version: SCHEMA_VERSION,
+        authors: rows
+            .iter()
+            .map(|row| Author {
+                author: row.author,
+                solo: row.solo,
+                paired: row.paired,
+                pairing_ratio: row.pairing_ratio,
+            })
+            .collect(),
+    };
+
+    serde_json::to_writer_pretty(&mut *out, &report)?;
+    writeln!(out)
+}
+
+pub(super) fn write_mob_sizes(out: &mut impl Write, rows: &[MobSizeRow<'_>]) -> io::Result<()> {
+    let report = MobSizesReport {
+        schema_version: SCHEMA_VERSION,
+        mob_sizes: rows
+            .iter()
+            .map(|row| MobSize {
+                author: row.author,
+                size: row.size,
+                commits: row.commits,
+            })
+            .collect(),
+    };
+
+    serde_json::to_writer_pretty(&mut *out, &report)?;
+    writeln!(out)
+}
+
+pub(super) fn write_timeline(out: &mut impl Write, rows: &[TimelineRow<'_>]) -> io::Result<()> {
+    let report = TimelineReport {
+        schema_version: SCHEMA_VERSION,
+        timeline: rows
+            .iter()
+            .map(|row| Period {
+                author: row.author,
+                period: &row.period,
+                solo: row.solo,
+                paired: row.paired,
+            })
+            .collect(),
+    };
+
+    serde_json::to_writer_pretty(&mut *out, &report)?;
+    writeln!(out)
+}
+
+pub(super) fn write_matrix(
+    out: &mut impl Write,
+    matrix: &Matrix<'_>,
+    weight: Weight,
+) -> io::Result<()> {
+    let report = MatrixReport {
+        schema_version: SCHEMA_VERSION,
+        weight,
+        authors: &matrix.authors,
+        as_driver: &matrix.as_driver,
+        asymmetric_pairs: matrix
+            .asymmetric_pairs()
+            .map(|(driver, navigator)| AsymmetricPair {
+                driver: matrix.authors[driver],
+                navigator: matrix.authors[navigator],
+                as_driver: matrix.as_driver[driver][navigator],
+                as_navigator: matrix.as_navigator(driver, navigator),
+            })
+            .collect(),
+    };
+
+    serde_json::to_writer_pretty(&mut *out, &report)?;
+    writeln!(out)
+}
+
+pub(super) fn write_suggestions(
+    out: &mut impl Write,
+    suggestions: &[Suggestion<'_>],
+    scoring: Scoring,
+) -> io::Result<()> {
+    let report = SuggestionsReport {
+        schema_version: SCHEMA_VERSION,
+        weight: scoring.weight,
+        half_life: scoring.half_life,
+        suggestions: suggestions
+            .iter()
+            .map(|suggestion| SuggestedPair {
+                author: suggestion.author,
+                co_author: suggestion.co_author,
+                together: suggestion.together,
+                last_paired: suggestion.last_paired.map(date::format),
+                score: suggestion.score,
+            })
+            .collect(),
+    };
+
+    serde_json::to_writer_pretty(&mut *out, &report)?;
+    writeln!(out)
+}
@@ -0,0 +1,984 @@
+use crate::{
+    suggest::{self, Scoring, Suggestion},
+    AuthorCounts, Bucket, Relationship, Repo, Result, Roster, ScanOptions, StringCache, Weight,
+};
+use clap::ValueEnum;
+use itertools::Itertools;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, Write},
+};
+
+mod delimited;
+mod html;
+mod json;
+mod table;
+
+/// The share of the commits that either author of a pair drove, which one author has to drive
+/// for the pair to be asymmetric.
+const ASYMMETRY_RATIO: f64 = 0.75;
+/// Pairs with fewer commits together are never asymmetric, a few commits say little about a pair.
+const MIN_ASYMMETRIC_COMMITS: u32 = 4;
+/// The team of authors that are not on the roster or have no team.
+const NO_TEAM: &str = "(no team)";
+
+/// The output format of a report.
+#[derive(ValueEnum, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Format {
+    /// Aligned plain-text table
+    Table,
+    /// JSON document with a schema version
+    Json,
+    /// Comma-separated values
+    Csv,
+    /// Tab-separated values
+    Tsv,
+}
+
+/// What a report lists.
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub(crate) enum View {
+    /// How often each author paired with each co-author
+    #[default]
+    Pairs,
+    /// How many commits of each author were solo or paired
+    Authors,
+    /// How many commits each author worked on by the number of people involved
+    MobSizes,
+    /// The solo and paired commits of each author over time, by week unless `--bucket` is given
+    Timeline,
+    /// How often each author drove while each other author navigated, marking pairs
+    /// where one author drove most of the time
+    Matrix,
+    /// How often the members of each team paired with the members of each team, requires a roster
+    Teams,
+}
+
+pub(crate) fn print_report(
+    mut repo: Repo,
+    options: &ScanOptions,
+    format: Format,
+    view: View,
+    weight: Weight,
+) -> Result<()> {
+    if view == View::Teams && repo.roster().is_empty() {
+        eyre::bail!("The teams view requires a roster, use `--roster`");
+    }
+
+    let bucket = options.bucket.unwrap_or(Bucket::Week);
+    let counts = match view {
+        View::Timeline => repo.extract_coauthors(&ScanOptions {
+            bucket: Some(bucket),
+            ..options.clone()
+        })?,
+        _ => repo.extract_coauthors(options)?,
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    match view {
+        View::Pairs => {
+            let rows = rows(counts, repo.string_cache(), weight);
+            write(&mut out, &rows, format, weight)?
+        }
+        View::Authors => {
+            let rows = author_rows(counts, repo.string_cache());
+            write_authors(&mut out, &rows, format)?
+        }
+        View::MobSizes => {
+            let rows = mob_size_rows(counts, repo.string_cache());
+            write_mob_sizes(&mut out, &rows, format)?
+        }
+        View::Timeline => {
+            let rows = timeline_rows(counts, repo.string_cache(), bucket);
+            write_timeline(&mut out, &rows, format)?
+        }
+        View::Matrix => {
+            let matrix = matrix(counts, repo.string_cache(), weight);
+            write_matrix(&mut out, &matrix, format, weight)?
+        }
+        View::Teams => {
+            let rows = team_rows(rows(counts, repo.string_cache(), weight), repo.roster());
+            write_teams(&mut out, &rows, format, weight)?
+        }
+    }
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Prints the matrix of [View::Matrix] as a self-contained HTML page with a heatmap.
+pub(crate) fn print_matrix_html(
+    mut repo: Repo,
+    options: &ScanOptions,
+    weight: Weight,
+) -> Result<()> {
+    let counts = repo.extract_coauthors(options)?;
+    let matrix = matrix(counts, repo.string_cache(), weight);
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    html::write_matrix(&mut out, &matrix, weight)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+fn write(out: &mut impl Write, rows: &[Row<'_>], format: Format, weight: Weight) -> io::Result<()> {
+    match format {
+        Format::Table => table::write(out, rows),
+        Format::Json => json::write(out, rows, weight),
+        Format::Csv => delimited::write(out, rows, b','),
+        Format::Tsv => delimited::write(out, rows, b'\t'),
+    }
+}
+
+fn write_teams(
+    out: &mut impl Write,
+    rows: &[Row<'_>],
+    format: Format,
+    weight: Weight,
+) -> io::Result<()> {
+    match format {
+        Format::Table => table::write_teams(out, rows),
+        Format::Json => json::write_teams(out, rows, weight),
+        Format::Csv => delimited::write_teams(out, rows, b','),
+        Format::Tsv => delimited::write_teams(out, rows, b'\t'),
+    }
+}
+
+fn write_authors(out: &mut impl Write, rows: &[AuthorRow<'_>], format: Format) -> io::Result<()> {
+    match format {
+        Format::Table => table::write_authors(out, rows),
+        Format::Json => json::write_authors(out, rows),
+        Format::Csv => delimited::write_authors(out, rows, b','),
+        Format::Tsv => delimited::write_authors(out, rows, b'\t'),
+    }
+}
+
+/// Prints who should pair next, see [suggest::plan].
+/// Pairs are weighted by `weight` and older commits count less if a `half_life` in days is given.
+///
+/// Authors are looked up on the roster by their name or alias. Without any authors, all active
+/// members are paired up if authors are filtered by the roster, even if they did not commit yet.
+pub(crate) fn print_suggestions(
+    mut repo: Repo,
+    options: &ScanOptions,
+    format: Format,
+    authors: &[String],
+    weight: Weight,
+    half_life: Option<f64>,
+) -> Result<()> {
+    if half_life.is_some_and(|half_life| half_life.is_nan() || half_life <= 0.0) {
+        eyre::bail!("The half-life must be a positive number of days");
+    }
+
+    let counts = repo
+        .extract_coauthors(options)?
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let roster = if authors.is_empty() && options.filters_members() {
+        repo.members(options)
+            .into_iter()
+            .map(|member| member.name.as_str())
+            .collect_vec()
+    } else {
+        authors
+            .iter()
+            .map(|author| match repo.roster().find(author, None) {
+                Some(member) => member.name.as_str(),
+                None => author.as_str(),
+            })
+            .collect_vec()
+    };
+    for author in &roster {
+        let committed = counts.keys().any(|&id| &repo.string_cache()[id] == *author);
+        if !authors.is_empty() && !committed {
+            eprintln!("{} did not commit in the scanned range", author);
+        }
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs() as i64);
+    let scoring = Scoring {
+        weight,
+        half_life,
+        now: options.until.unwrap_or(now),
+    };
+    let suggestions = suggest::plan(&counts, repo.string_cache(), &roster, scoring);
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    write_suggestions(&mut out, &suggestions, format, scoring)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+fn write_suggestions(
+    out: &mut impl Write,
+    suggestions: &[Suggestion<'_>],
+    format: Format,
+    scoring: Scoring,
+) -> io::Result<()> {
+    match format {
+        Format::Table => table::write_suggestions(out, suggestions),
+        Format::Json => json::write_suggestions(out, suggestions, scoring),
+        Format::Csv => delimited::write_suggestions(out, suggestions, b','),
+        Format::Tsv => delimited::write_suggestions(out, suggestions, b'\t'),
+    }
+}
+
+/// Prints how every author and co-author is resolved to an identity.
+pub(crate) fn print_identities(repo: &Repo, options: &ScanOptions) -> Result<()> {
+    let cells = repo
+        .explain_identities(options)?
+        .into_iter()
+        .map(|explanation| {
+            let name = match explanation.mail {
+                Some(mail) => format!("{} <{}>", explanation.name, mail),
+                None => explanation.name,
+            };
+            [
+                name,
+                explanation.mailmap.unwrap_or_default(),
+                explanation.rule.unwrap_or_default(),
+                explanation.identity,
+            ]
+        })
+        .collect_vec();
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    table::write_cells(
+        &mut out,
+        &["Author", "Mailmap", "Rule", "Identity"],
+        &cells,
+        4,
+    )?;
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Prints every malformed trailer and every co-author that is not on the roster,
+/// and returns whether there were none.
+pub(crate) fn print_lint(repo: &Repo, options: &ScanOptions) -> Result<bool> {
+    let malformed = repo.lint(options)?;
+    let unlisted = repo.unlisted_co_authors(options)?;
+    let is_clean = malformed.is_empty() && unlisted.is_empty();
+    let has_malformed = !malformed.is_empty();
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    if has_malformed {
+        let commits = malformed.iter().map(|m| &m.commit).unique().count();
+        let cells = malformed
+            .into_iter()
+            .map(|m| [m.commit, m.author, m.line, m.error.to_string()])
+            .collect_vec();
+        table::write_cells(&mut out, &["Commit", "Author", "Line", "Error"], &cells, 4)?;
+        eprintln!(
+            "Found {} malformed trailer(s) in {} commit(s)",
+            cells.len(),
+            commits
+        );
+    }
+    if !unlisted.is_empty() {
+        let co_authors = unlisted.iter().map(|u| &u.co_author).unique().count();
+        let commits = unlisted.iter().map(|u| &u.commit).unique().count();
+        let cells = unlisted
+            .into_iter()
+            .map(|u| [u.commit, u.author, u.co_author])
+            .collect_vec();
+        if has_malformed {
+            writeln!(out)?;
+        }
+        table::write_cells(&mut out, &["Commit", "Author", "Co-author"], &cells, 3)?;
+        eprintln!(
+            "Found {} co-author(s) that are not on the roster in {} commit(s)",
+            co_authors, commits
+        );
+    }
+    out.flush()?;
+
+    Ok(is_clean)
+}
+
+fn write_matrix(
+    out: &mut impl Write,
+    matrix: &Matrix<'_>,
+    format: Format,
+    weight: Weight,
+) -> io::Result<()> {
+    match format {
+        Format::Table => table::write_matrix(out, matrix),
+        Format::Json => json::write_matrix(out, matrix, weight),
+        Format::Csv => delimited::write_matrix(out, matrix, b','),
+        Format::Tsv => delimited::write_matrix(out, matrix, b'\t'),
+    }
+}
+
+/// A pair of authors, with their commits weighted by the [Weight] of the report.
+struct Row<'a> {
+    author: &'a str,
+    co_author: &'a str,
+    as_driver: u64,
+    total: u64,
+}
+
+fn write_mob_sizes(
+    out: &mut impl Write,
+    rows: &[MobSizeRow<'_>],
+    format: Format,
+) -> io::Result<()> {
+    match format {
+        Format::Table => table::write_mob_sizes(out, rows),
+        Format::Json => json::write_mob_sizes(out, rows),
+        Format::Csv => delimited::write_mob_sizes(out, rows, b','),
+        Format::Tsv => delimited::write_mob_sizes(out, rows, b'\t'),
+    }
+}
+
+fn write_timeline(
+    out: &mut impl Write,
+    rows: &[TimelineRow<'_>],
+    format: Format,
+) -> io::Result<()> {
+    match format {
+        Format::Table => table::write_timeline(out, rows),
+        Format::Json => json::write_timeline(out, rows),
+        Format::Csv => delimited::write_timeline(out, rows, b','),
+        Format::Tsv => delimited::write_timeline(out, rows, b'\t'),
+    }
+}
+
+struct AuthorRow<'a> {
+    author: &'a str,
+    solo: u32,
+    paired: u32,
+    pairing_ratio: f64,
+}
+
+struct MobSizeRow<'a> {
+    author: &'a str,
+    size: usize,
+    commits: u32,
+}
+
+struct TimelineRow<'a> {
+    author: &'a str,
+    period: String,
+    solo: u32,
+    paired: u32,
+}
+
+/// The pairs of all authors that committed or co-authored, sorted by name.
+/// The first index is the driver and the second index the navigator.
+struct Matrix<'a> {
+    authors: Vec<&'a str>,
+    as_driver: Vec<Vec<u64>>,
+    totals: Vec<Vec<u64>>,
+    asymmetric: Vec<Vec<bool>>,
+}
+
+impl Matrix<'_> {
+    /// How often the navigator drove, or navigated with a third author, while working with the driver.
+    fn as_navigator(&self, driver: usize, navigator: usize) -> u64 {
+        self.totals[driver][navigator] - self.as_driver[driver][navigator]
+    }
+
+    fn max(&self) -> u64 {
+        self.as_driver
+            .iter()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The pairs where the driver drove most of the time, by driver and navigator.
+    fn asymmetric_pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.asymmetric
+            .iter()
+            .enumerate()
+            .flat_map(|(driver, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, &asymmetric)| asymmetric)
+                    .map(move |(navigator, _)| (driver, navigator))
+            })
+    }
+}
+
+/// Sums the pairs by the teams of both authors, sorted by team and co-team.
+fn team_rows<'a>(rows: Vec<Row<'_>>, roster: &'a Roster) -> Vec<Row<'a>> {
+    let team = |author: &str| {
+        roster
+            .member(author)
+            .and_then(|member| member.team.as_deref())
+            .unwrap_or(NO_TEAM)
+    };
+
+    let mut teams = BTreeMap::<(&str, &str), (u64, u64)>::new();
+    for row in rows {
+        let (as_driver, total) = teams
+            .entry((team(row.author), team(row.co_author)))
+            .or_default();
+        *as_driver += row.as_driver;
+        *total += row.total;
+    }
+
+    teams
+        .into_iter()
+        .map(|((team, co_team), (as_driver, total))| Row {
+            author: team,
+            co_author: co_team,
+            as_driver,
+            // each pair within a team is seen from both authors
+            total: if team == co_team { total / 2 } else { total },
+        })
+        .collect()
+}
+
+/// Flattens the counts into one row per pair, sorted by author and co-author.
+fn rows(counts: AuthorCounts, string_cache: &StringCache, weight: Weight) -> Vec<Row<'_>> {
+    counts
+        .into_resolving_iter(string_cache)
+        .flat_map(|(author, counts)| {
+            counts
+                .resolving_iter(string_cache)
+                .filter(|(_, paired_with)| paired_with.total > 0)
+                .map(|(co_author, paired_with)| Row {
+                    author,
+                    co_author,
+                    as_driver: paired_with.weighted_as_author_of(Relationship::Pairing, weight),
+                    total: paired_with.weighted_total_of(Relationship::Pairing, weight),
+                })
+                .collect_vec()
+        })
+        .sorted_by(|lhs, rhs| (lhs.author, lhs.co_author).cmp(&(rhs.author, rhs.co_author)))
+        .collect()
+}
+
+/// One row per author that committed or co-authored any commit, sorted by author.
+fn author_rows(counts: AuthorCounts, string_cache: &StringCache) -> Vec<AuthorRow<'_>> {
+    counts
+        .into_resolving_iter(string_cache)
+        .filter(|(_, counts)| counts.commits() > 0)
+        .map(|(author, counts)| AuthorRow {
+            author,
+            solo: counts.solo(),
+            paired: counts.paired(),
+            pairing_ratio: counts.pairing_ratio().unwrap_or_default(),
+        })
+        .sorted_by(|lhs, rhs| lhs.author.cmp(rhs.author))
+        .collect()
+}
+
+/// Flattens the counts into one row per author and group size, sorted by author and size.
+fn mob_size_rows(counts: AuthorCounts, string_cache: &StringCache) -> Vec<MobSizeRow<'_>> {
+    counts
+        .into_resolving_iter(string_cache)
+        .sorted_by(|lhs, rhs| lhs.0.cmp(rhs.0))
+        .flat_map(|(author, counts)| {
+            counts
+                .group_sizes()
+                .map(|(size, commits)| MobSizeRow {
+                    author,
+                    size,
+                    commits,
+                })
+                .collect_vec()
+        })
+        .collect()
+}
+
+/// Flattens the counts into one row per author and period with commits, sorted by author and time.
+fn timeline_rows(
+    counts: AuthorCounts,
+    string_cache: &StringCache,
+    bucket: Bucket,
+) -> Vec<TimelineRow<'_>> {
+    counts
+        .into_resolving_iter(string_cache)
+        .sorted_by(|lhs, rhs| lhs.0.cmp(rhs.0))
+        .flat_map(|(author, counts)| {
+            counts
+                .timeline()
+                .map(|(start, activity)| TimelineRow {
+                    author,
+                    period: bucket.label(start),
+                    solo: activity.solo,
+                    paired: activity.paired,
+                })
+                .collect_vec()
+        })
+        .collect()
+}
+
+/// Builds the matrix of all authors that committed or co-authored.
+fn matrix(counts: AuthorCounts, string_cache: &StringCache, weight: Weight) -> Matrix<'_> {
+    // keyed by the id, as different authors can have the same name, e.g. with different emails
+    let counts = counts
+        .into_iter()
+        .filter(|(_, counts)| counts.commits() > 0)
+        .sorted_by_key(|&(author, _)| (&string_cache[author], author))
+        .collect_vec();
+    let index = counts
+        .iter()
+        .enumerate()
+        .map(|(index, &(author, _))| (author, index))
+        .collect::<HashMap<_, _>>();
+
+    let mut as_driver = vec![vec![0; counts.len()]; counts.len()];
+    let mut totals = as_driver.clone();
+    let mut commits_as_driver = vec![vec![0; counts.len()]; counts.len()];
+    for (driver, (_, pairing_counts)) in counts.iter().enumerate() {
+        for (co_author, paired_with) in pairing_counts.iter() {
+            let navigator = match index.get(&co_author) {
+                Some(&navigator) => navigator,
+                None => continue,
+            };
+            as_driver[driver][navigator] =
+                paired_with.weighted_as_author_of(Relationship::Pairing, weight);
+            totals[driver][navigator] =
+                paired_with.weighted_total_of(Relationship::Pairing, weight);
+            commits_as_driver[driver][navigator] = paired_with.as_driver;
+        }
+    }
+
+    // in a mob, the total also counts the commits where both authors navigated, which say nothing
+    // about who drives more, so only the commits that one of them drove are compared
+    let mut asymmetric = vec![vec![false; counts.len()]; counts.len()];
+    for driver in 0..counts.len() {
+        for navigator in 0..counts.len() {
+            let drove = as_driver[driver][navigator];
+            let drove_together = drove + as_driver[navigator][driver];
+            let commits =
+                commits_as_driver[driver][navigator] + commits_as_driver[navigator][driver];
+            asymmetric[driver][navigator] = commits >= MIN_ASYMMETRIC_COMMITS
+                && drove as f64 >= drove_together as f64 * ASYMMETRY_RATIO;
+        }
+    }
+
+    Matrix {
+        authors: counts
+            .iter()
+            .map(|&(author, _)| &string_cache[author])
+            .collect(),
+        as_driver,
+        totals,
+        asymmetric,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git_stats_core::{date, DiffStats, Member};
+
+    fn counts_for(string_cache: &mut StringCache) -> AuthorCounts {
+        let alice = string_cache.intern("Alice");
+        let bob = string_cache.intern("Bob");
+        let charlie = string_cache.intern("Charlie, Jr.");
+
+        let lines = |insertions| DiffStats {
+            insertions,
+            deletions: 0,
+            files: 1,
+        };
+
+        let mut counts = AuthorCounts::default();
+        counts.add_commit(bob, &[alice], lines(10));
+        counts.add_commit(alice, &[bob], lines(5));
+        counts.add_commit(alice, &[bob], lines(20));
+        counts.add_commit(alice, &[], lines(1));
+        counts.add_commit(charlie, &[alice], lines(2));
+        counts
+    }
+
+    fn render_weighted(format: Format, weight: Weight) -> String {
+        let mut string_cache = StringCache::new();
+        let counts = counts_for(&mut string_cache);
+        let rows = rows(counts, &string_cache, weight);
+        let mut out = Vec::new();
+        write(&mut out, &rows, format, weight).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn render(format: Format) -> String {
+        render_weighted(format, Weight::Commits)
+    }
+
+    fn render_authors(format: Format) -> String {
+        let mut string_cache = StringCache::new();
+        let counts = counts_for(&mut string_cache);
+        let rows = author_rows(counts, &string_cache);
+        let mut out = Vec::new();
+        write_authors(&mut out, &rows, format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_table() {
+        assert_eq!(
+            render(Format::Table),
+            "\
+Author        Co-author     Driver  Navigator  Total
+Alice         Bob                2          1      3
+Alice         Charlie, Jr.       0          1      1
+Bob           Alice              1          2      3
+Charlie, Jr.  Alice              1          0      1
+"
+        );
+    }
+
+    #[test]
+    fn test_table_by_lines() {
+        assert_eq!(
+            render_weighted(Format::Table, Weight::Lines),
+            "\
+Author        Co-author     Driver  Navigator  Total
+Alice         Bob               25         10     35
+Alice         Charlie, Jr.       0          2      2
+Bob           Alice             10         25     35
+Charlie, Jr.  Alice              2          0      2
+"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            render(Format::Csv),
+            "\
+author,co_author,as_driver,total
+Alice,Bob,2,3
+Alice,\"Charlie, Jr.\",0,1
+Bob,Alice,1,3
+\"Charlie, Jr.\",Alice,1,1
+"
+        );
+    }
+
+    #[test]
+    fn test_tsv() {
+        assert_eq!(
+            render(Format::Tsv),
+            "\
+author\tco_author\tas_driver\ttotal
+Alice\tBob\t2\t3
+Alice\tCharlie, Jr.\t0\t1
+Bob\tAlice\t1\t3
+Charlie, Jr.\tAlice\t1\t1
+"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let json: serde_json::Value = serde_json::from_str(&render(Format::Json)).unwrap();
+        assert_eq!(json["schema_version"], 2);
+        assert_eq!(json["weight"], "commits");
+        assert_eq!(json["pairs"].as_array().unwrap().len(), 4);
+        assert_eq!(
+            json["pairs"][0],
+            serde_json::json!({
+                "author": "Alice",
+                "co_author": "Bob",
+                "as_driver": 2,
+                "total": 3,
+            })
+        );
+    }
+
+    #[test]
+    fn test_authors_table() {
+        assert_eq!(
+            render_authors(Format::Table),
+            "\
+Author        Commits  Solo  Paired  Pairing
+Alice               5     1       4      80%
+Bob                 3     0       3     100%
+Charlie, Jr.        1     0       1     100%
+"
+        );
+    }
+
+    #[test]
+    fn test_authors_csv() {
+        assert_eq!(
+            render_authors(Format::Csv),
+            "\
+author,solo,paired,pairing_ratio
+Alice,1,4,0.800
+Bob,0,3,1.000
+\"Charlie, Jr.\",0,1,1.000
+"
+        );
+    }
+
+    #[test]
+    fn test_authors_json() {
+        let json: serde_json::Value = serde_json::from_str(&render_authors(Format::Json)).unwrap();
+        assert_eq!(json["schema_version"], 2);
+        assert_eq!(
+            json["authors"][0],
+            serde_json::json!({
+                "author": "Alice",
+                "solo": 1,
+                "paired": 4,
+                "pairing_ratio": 0.8,
+            })
+        );
+    }
+
+    #[test]
+    fn test_mob_sizes_table() {
+        let mut string_cache = StringCache::new();
+        let counts = counts_for(&mut string_cache);
+        let rows = mob_size_rows(counts, &string_cache);
+        let mut out = Vec::new();
+        write_mob_sizes(&mut out, &rows, Format::Table).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+Author        Size  Commits
+Alice            1        1
+Alice            2        4
+Bob              2        3
+Charlie, Jr.     2        1
+"
+        );
+    }
+
+    #[test]
+    fn test_timeline_table() {
+        let mut string_cache = StringCache::new();
+        let alice = string_cache.intern("Alice");
+        let bob = string_cache.intern("Bob");
+        let month = |date| Bucket::Month.start_of(date::parse_since(date).unwrap());
+
+        let mut counts = AuthorCounts::default();
+        counts.add_to_timeline(month("2022-10-05"), alice, &[]);
+        counts.add_to_timeline(month("2022-10-31"), alice, &[bob]);
+        counts.add_to_timeline(month("2022-12-01"), bob, &[]);
+
+        let rows = timeline_rows(counts, &string_cache, Bucket::Month);
+        let mut out = Vec::new();
+        write_timeline(&mut out, &rows, Format::Table).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+Author  Period   Solo  Paired
+Alice   2022-10     1       1
+Bob     2022-10     0       1
+Bob     2022-12     1       0
+"
+        );
+    }
+
+    fn render_matrix(format: Format) -> String {
+        render_matrix_with(|out, matrix| write_matrix(out, matrix, format, Weight::Commits))
+    }
+
+    fn render_matrix_with(
+        write: impl FnOnce(&mut Vec<u8>, &Matrix<'_>) -> io::Result<()>,
+    ) -> String {
+        let mut string_cache = StringCache::new();
+        let alice = string_cache.intern("Alice");
+        let bob = string_cache.intern("Bob <3");
+        let charlie = string_cache.intern("Charlie, Jr.");
+
+        let mut counts = AuthorCounts::default();
+        for _ in 0..3 {
+            counts.add_commit(alice, &[bob], DiffStats::default());
+        }
+        counts.add_commit(bob, &[alice], DiffStats::default());
+        counts.add_commit(charlie, &[alice], DiffStats::default());
+
+        let matrix = matrix(counts, &string_cache, Weight::Commits);
+        let mut out = Vec::new();
+        write(&mut out, &matrix).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_matrix_table() {
+        assert_eq!(
+            render_matrix(Format::Table),
+            "\
+Driver \\ Navigator  Alice  Bob <3  Charlie, Jr.
+Alice                   -      3*             0
+Bob <3                  1       -             0
+Charlie, Jr.            1       0             -
+
+* drove at least 75% of the time with this navigator, consider switching roles
+"
+        );
+    }
+
+    #[test]
+    fn test_matrix_csv() {
+        assert_eq!(
+            render_matrix(Format::Csv),
+            "\
+driver,Alice,Bob <3,\"Charlie, Jr.\"
+Alice,,3,0
+Bob <3,1,,0
+\"Charlie, Jr.\",1,0,
+"
+        );
+    }
+
+    #[test]
+    fn test_matrix_json() {
+        let json: serde_json::Value = serde_json::from_str(&render_matrix(Format::Json)).unwrap();
+        assert_eq!(
+            json["authors"],
+            serde_json::json!(["Alice", "Bob <3", "Charlie, Jr."])
+        );
+        assert_eq!(
+            json["as_driver"],
+            serde_json::json!([[0, 3, 0], [1, 0, 0], [1, 0, 0]])
+        );
+        // a single commit together is not asymmetric
+        assert_eq!(
+            json["asymmetric_pairs"],
+            serde_json::json!([{
+                "driver": "Alice",
+                "navigator": "Bob <3",
+                "as_driver": 3,
+                "as_navigator": 1,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_matrix_html() {
+        let html =
+            render_matrix_with(|out, matrix| html::write_matrix(out, matrix, Weight::Commits));
+        assert!(html.contains(r#"<th scope="col">Bob &lt;3</th>"#));
+        assert!(html.contains(
+            r#"<td class="asymmetric" style="background: rgba(31, 119, 180, 1.00)" title="Alice drove 3 and navigated 1 of the commits with Bob &lt;3">3</td>"#
+        ));
+        assert_eq!(html.matches("class=\"asymmetric\"").count(), 1);
+    }
+
+    #[test]
+    fn test_matrix_mob() {
+        let mut string_cache = StringCache::new();
+        let [alice, bob, charlie] =
+            ["Alice", "Bob", "Charlie"].map(|name| string_cache.intern(name));
+
+        let mut counts = AuthorCounts::default();
+        for _ in 0..8 {
+            counts.add_mob_commit(alice, &[bob, charlie], DiffStats::default());
+        }
+        for _ in 0..3 {
+            counts.add_mob_commit(bob, &[charlie], DiffStats::default());
+        }
+        counts.add_mob_commit(charlie, &[bob], DiffStats::default());
+
+        let matrix = matrix(counts, &string_cache, Weight::Commits);
+        // Bob drove 3 of the 4 commits with Charlie that one of them drove, the 8 commits
+        // where both navigated are not compared
+        assert_eq!(matrix.totals[1][2], 12);
+        assert_eq!(
+            matrix.asymmetric_pairs().collect_vec(),
+            [(0, 1), (0, 2), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_matrix_same_name() {
+        let mut string_cache = StringCache::new();
+        let alex = string_cache.intern_with_label("alex@example.com", "Alex");
+        let other_alex = string_cache.intern_with_label("alex@example.org", "Alex");
+        let bob = string_cache.intern_with_label("bob@example.com", "Bob");
+
+        let mut counts = AuthorCounts::default();
+        counts.add_commit(alex, &[bob], DiffStats::default());
+        for _ in 0..2 {
+            counts.add_commit(other_alex, &[bob], DiffStats::default());
+        }
+
+        let matrix = matrix(counts, &string_cache, Weight::Commits);
+        assert_eq!(matrix.authors, ["Alex", "Alex", "Bob"]);
+        assert_eq!(
+            matrix.as_driver,
+            [vec![0, 0, 1], vec![0, 0, 2], vec![0, 0, 0]]
+        );
+    }
+
+    #[test]
+    fn test_suggestions_table() {
+        let suggestions = [
+            Suggestion {
+                author: "Alice",
+                co_author: Some("Charlie, Jr."),
+                together: 2,
+                last_paired: Some(date::parse_since("2022-10-05").unwrap()),
+                score: 2.0,
+            },
+            Suggestion {
+                author: "Bob",
+                co_author: Some("Dave"),
+                together: 0,
+                last_paired: None,
+                score: 0.0,
+            },
+            Suggestion {
+                author: "Eve",
+                co_author: None,
+                together: 0,
+                last_paired: None,
+                score: 0.0,
+            },
+        ];
+        let scoring = Scoring {
+            weight: Weight::Commits,
+            half_life: None,
+            now: 0,
+        };
+        let mut out = Vec::new();
+        write_suggestions(&mut out, &suggestions, Format::Table, scoring).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+Author  Co-author     Together  Last paired
+Alice   Charlie, Jr.         2   2022-10-05
+Bob     Dave                 0        never
+Eve
+"
+        );
+    }
+
+    #[test]
+    fn test_teams_table() {
+        let mut string_cache = StringCache::new();
+        let counts = counts_for(&mut string_cache);
+        let member = |name: &str| Member {
+            name: name.into(),
+            team: Some("platform".into()),
+            ..Member::default()
+        };
+        let roster = Roster::new(vec![member("Alice"), member("Bob")]).unwrap();
+        let rows = team_rows(rows(counts, &string_cache, Weight::Commits), &roster);
+        let mut out = Vec::new();
+        write_teams(&mut out, &rows, Format::Table, Weight::Commits).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+Team       Co-team    Driver  Navigator  Total
+(no team)  platform        1          0      1
+platform   (no team)       0          1      1
+platform   platform        3          0      3
+"
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use super::{AuthorRow, Matrix, MobSizeRow, Row, TimelineRow};
+use crate::{suggest::Suggestion, Bucket};
+use itertools::Itertools;
+use std::io::{self, Write};
+use unicode_width::UnicodeWidthStr;
+
+const HEADER: [&str; 5] = ["Author", "Co-author", "Driver", "Navigator", "Total"];
+const TEAMS_HEADER: [&str; 5] = ["Team", "Co-team", "Driver", "Navigator", "Total"];
+const AUTHORS_HEADER: [&str; 5] = ["Author", "Commits", "Solo", "Paired", "Pairing"];
+const MOB_SIZES_HEADER: [&str; 3] = ["Author", "Size", "Commits"];
+const TIMELINE_HEADER: [&str; 4] = ["Author", "Period", "Solo", "Paired"];
+const SUGGESTIONS_HEADER: [&str; 4] = ["Author", "Co-author", "Together", "Last paired"];
+const MATRIX_CORNER: &str = "Driver \\ Navigator";
+const ASYMMETRIC_MARKER: &str = "*";
+const COLUMN_GAP: &str = "  ";
+
+pub(super) fn write(out: &mut impl Write, rows: &[Row<'_>]) -> io::Result<()> {
+    write_cells(out, &HEADER, &pair_cells(rows), 2)
+}
+
+/// Writes one row per pair of teams, where the driver is on the first team.
+pub(super) fn write_teams(out: &mut impl Write, rows: &[Row<'_>]) -> io::Result<()> {
+    write_cells(out, &TEAMS_HEADER, &pair_cells(rows), 2)
+}
+
+fn pair_cells(rows: &[Row<'_>]) -> Vec<[String; 5]> {
+    rows.iter()
+        .map(|row| {
+            [
+                row.author.to_string(),
+                row.co_author.to_string(),
+                row.as_driver.to_string(),
+                (row.total - row.as_driver).to_string(),
+                row.total.to_string(),
+            ]
+        })
+        .collect_vec()
+}
+
+pub(super) fn write_authors(out: &mut impl Write, rows: &[AuthorRow<'_>]) -> io::Result<()> {
+    let cells = rows
+        .iter()
+        .map(|row| {
+            [
+                row.author.to_string(),
+                (row.solo + row.paired).to_string(),
+                row.solo.to_string(),
+                row.paired.to_string(),
+                format!("{:.0}%", row.pairing_ratio * 100.0),
+            ]
+        })
+        .collect_vec();
+
+    write_cells(out, &AUTHORS_HEADER, &cells, 1)
+}
+
+pub(super) fn write_mob_sizes(out: &mut impl Write, rows: &[MobSizeRow<'_>]) -> io::Result<()> {
+    let cells = rows
+        .iter()
+        .map(|row| {
+            [
+                row.author.to_string(),
+                row.size.to_string(),
+                row.commits.to_string(),
+            ]
+        })
+        .collect_vec();
+
+    write_cells(out, &MOB_SIZES_HEADER, &cells, 1)
+}
+
+pub(super) fn write_timeline(out: &mut impl Write, rows: &[TimelineRow<'_>]) -> io::Result<()> {
+    let cells = rows
+        .iter()
+        .map(|row| {
+            [
+                row.author.to_string(),
+                row.period.clone(),
+                row.solo.to_string(),
+                row.paired.to_string(),
+            ]
+        })
+        .collect_vec();
+
+    write_cells(out, &TIMELINE_HEADER, &cells, 2)
+}
+
+/// Writes one row per suggested pair. An author without a partner has no co-author.
+pub(super) fn write_suggestions(
+    out: &mut impl Write,
+    suggestions: &[Suggestion<'_>],
+) -> io::Result<()> {
+    let cells = suggestions
+        .iter()
+        .map(|suggestion| match suggestion.co_author {
+            Some(co_author) => [
+                suggestion.author.to_string(),
+                co_author.to_string(),
+                suggestion.together.to_string(),
+                suggestion
+                    .last_paired
+                    .map_or_else(|| "never".to_string(), |time| Bucket::Day.label(time)),
+            ],
+            None => [
+                suggestion.author.to_string(),
+                String::new(),
+                String::new(),
+                String::new(),
+            ],
+        })
+        .collect_vec();
+
+    write_cells(out, &SUGGESTIONS_HEADER, &cells, 2)
+}
+
+/// Writes one row per driver and one column per navigator.
+/// Asymmetric pairs are marked and explained below the table.
+pub(super) fn write_matrix(out: &mut impl Write, matrix: &Matrix<'_>) -> io::Result<()> {
+    let header = std::iter::once(MATRIX_CORNER)
+        .chain(matrix.authors.iter().copied())
+        .collect_vec();
+    let cells = matrix
+        .authors
+        .iter()
+        .enumerate()
+        .map(|(driver, author)| {
+            std::iter::once(author.to_string())
+                .chain((0..matrix.authors.len()).map(|navigator| {
+                    if driver == navigator {
+                        "-".to_string()
+                    } else if matrix.asymmetric[driver][navigator] {
+                        format!(
+                            "{}{}",
+                            matrix.as_driver[driver][navigator], ASYMMETRIC_MARKER
+                        )
+                    } else {
+                        matrix.as_driver[driver][navigator].to_string()
+                    }
+                }))
+                .collect_vec()
+        })
+        .collect_vec();
+
+    write_cells(out, &header, &cells, 1)?;
+    if matrix
+        .asymmetric
+        .iter()
+        .flatten()
+        .any(|&asymmetric| asymmetric)
+    {
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{} drove at least {:.0}% of the time with this navigator, consider switching roles",
+            ASYMMETRIC_MARKER,
+            super::ASYMMETRY_RATIO * 100.0
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Writes an aligned table. The first `left_aligned` columns are aligned to the left,
+/// all other columns are aligned to the right.
+pub(super) fn write_cells(
+    out: &mut impl Write,
+    header: &[&str],
+    cells: &[impl AsRef<[String]>],
+    left_aligned: usize,
+) -> io::Result<()> {
+    let mut widths = header.iter().map(|cell| cell.width()).collect_vec();
+    for row in cells {
+        for (width, cell) in widths.iter_mut().zip(row.as_ref()) {
+            *width = (*width).max(cell.width());
+        }
+    }
+
+    write_line(out, &widths, header, left_aligned)?;
+    for row in cells {
+        write_line(out, &widths, row.as_ref(), left_aligned)?;
+    }
+
+    Ok(())
+}
+
+fn write_line(
+    out: &mut impl Write,
+    widths: &[usize],
+    cells: &[impl AsRef<str>],
+    left_aligned: usize,
+) -> io::Result<()> {
+    let mut line = String::new();
+    for (column, (cell, width)) in cells.iter().zip(widths).enumerate() {
+        let cell = cell.as_ref();
+        let padding = " ".repeat(width.saturating_sub(cell.width()));
+        if column > 0 {
+            line.push_str(COLUMN_GAP);
+        }
+        if column < left_aligned {
+            line.push_str(cell);
+            line.push_str(&padding);
+        } else {
+            line.push_str(&padding);
+            line.push_str(cell);
+        }
+    }
+    writeln!(out, "{}", line.trim_end())
+}
@@ -0,0 +1,110 @@
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::{date, Member, Result, Roster};
+
+/// The roster file, which lists every member of the team.
+///
+/// # Example
+///
+/// ```toml
+/// [[member]]
+/// name = "Alice Keys"
+/// emails = ["alice@wonderland.org", "alice@users.noreply.github.com"]
+/// aliases = ["alice", "A. Keys"]
+/// team = "platform"
+/// since = "2021-03-01"
+/// until = "2023-06-30"
+/// ```
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+struct RosterFile {
+    member: Vec<MemberEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct MemberEntry {
+    name: String,
+    #[serde(default)]
+    emails: Vec<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    team: Option<String>,
+    /// The day the member joined, in any format of `--since`.
+    since: Option<String>,
+    /// The day the member left, in any format of `--until`.
+    until: Option<String>,
+}
+
+impl MemberEntry {
+    fn into_member(self) -> Result<Member> {
+        Ok(Member {
+            active_since: self.since.as_deref().map(date::parse_since).transpose()?,
+            active_until: self.until.as_deref().map(date::parse_until).transpose()?,
+            name: self.name,
+            emails: self.emails,
+            aliases: self.aliases,
+            team: self.team,
+        })
+    }
+}
+
+/// Reads the roster from a TOML file.
+pub(crate) fn load(path: &Path) -> Result<Roster> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| eyre!("Could not read `{}`: {}", path.display(), err))?;
+    parse(&content).map_err(|err| eyre!("Invalid roster file `{}`: {}", path.display(), err))
+}
+
+fn parse(content: &str) -> Result<Roster> {
+    let file: RosterFile = toml::from_str(content)?;
+    let members = file
+        .member
+        .into_iter()
+        .map(MemberEntry::into_member)
+        .collect::<Result<_>>()?;
+    Ok(Roster::new(members)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let roster = parse(
+            r#"
+            [[member]]
+            name = "Alice Keys"
+            emails = ["alice@wonderland.org"]
+            aliases = ["alice"]
+            team = "platform"
+            since = "2021-03-01"
+
+            [[member]]
+            name = "dependabot[bot]"
+            until = "2022-01-01"
+            "#,
+        )
+        .unwrap();
+
+        let alice = roster.find("alice", None).unwrap();
+        assert_eq!(alice.name, "Alice Keys");
+        assert_eq!(alice.team.as_deref(), Some("platform"));
+        assert_eq!(
+            alice.active_since,
+            Some(date::parse_since("2021-03-01").unwrap())
+        );
+        assert_eq!(alice.active_until, None);
+        assert_eq!(roster.members().len(), 2);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(parse("[[member]]\nemails = []\n").is_err());
+        assert!(parse("[[member]]\nname = \"Alice\"\nsince = \"someday\"\n").is_err());
+        assert!(parse("[[member]]\nname = \"Alice\"\nrole = \"lead\"\n").is_err());
+    }
+}
@@ -0,0 +1,380 @@
+use crate::{PairingCounts, Relationship, StringCache, Weight};
+use git_stats_core::PairedWith;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// Larger rosters are planned greedily, as the best plan takes exponential time to find.
+const MAX_EXACT_ROSTER: usize = 20;
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// How the history of a pair counts against pairing them again.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Scoring {
+    pub(crate) weight: Weight,
+    /// After this many days, the commits of a pair count only half as much.
+    /// Without a half-life, all commits in the scanned range count the same.
+    pub(crate) half_life: Option<f64>,
+    /// The time the age of the commits is measured from, in seconds since the epoch.
+    pub(crate) now: i64,
+}
+
+/// A suggested pair, or an author without a partner if the roster has an odd size.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Suggestion<'a> {
+    pub(crate) author: &'a str,
+    pub(crate) co_author: Option<&'a str>,
+    /// How much both authors worked together, weighted by the [Weight] of the [Scoring].
+    pub(crate) together: u64,
+    /// The time of the latest commit of both authors together, in seconds since the epoch.
+    pub(crate) last_paired: Option<i64>,
+    /// How much the history of the pair counts against pairing them, lower is better.
+    pub(crate) score: f64,
+}
+
+/// An author on the roster, with the id of their commits if they committed in the scanned range.
+#[derive(Copy, Clone, Debug)]
+struct Author<'a> {
+    id: Option<usize>,
+    name: &'a str,
+}
+
+/// The history of the authors on a roster, by their position on the roster.
+struct History {
+    paired: HashMap<(usize, usize), PairedWith>,
+}
+
+impl History {
+    fn new(counts: &HashMap<usize, PairingCounts>, roster: &[Author<'_>]) -> Self {
+        let position = roster
+            .iter()
+            .enumerate()
+            .filter_map(|(position, author)| Some((author.id?, position)))
+            .collect::<HashMap<_, _>>();
+
+        let mut paired = HashMap::new();
+        for (author, counts) in counts {
+            let author = match position.get(author) {
+                Some(&author) => author,
+                None => continue,
+            };
+            for (co_author, paired_with) in counts.iter() {
+                if let Some(&co_author) = position.get(&co_author) {
+                    paired.insert((author, co_author), paired_with);
+                }
+            }
+        }
+        History { paired }
+    }
+
+    fn suggestion<'a>(
+        &self,
+        roster: &[Author<'a>],
+        author: usize,
+        co_author: Option<usize>,
+        scoring: Scoring,
+    ) -> Suggestion<'a> {
+        let paired_with = co_author.and_then(|co_author| self.paired.get(&(author, co_author)));
+        let together = paired_with.map_or(0, |paired_with| {
+            paired_with.weighted_total_of(Relationship::Pairing, scoring.weight)
+        });
+        let last_paired = paired_with.and_then(|paired_with| paired_with.last_paired);
+        let decay = match (scoring.half_life, last_paired) {
+            (Some(half_life), Some(last_paired)) => {
+                let age = (scoring.now - last_paired).max(0) as f64 / SECONDS_PER_DAY;
+                0.5_f64.powf(age / half_life)
+            }
+            _ => 1.0,
+        };
+        Suggestion {
+            author: roster[author].name,
+            co_author: co_author.map(|co_author| roster[co_author].name),
+            together,
+            last_paired,
+            score: together as f64 * decay,
+        }
+    }
+}
+
+/// Splits the roster into pairs, so that the authors of a pair worked together as little as possible.
+/// If the roster has an odd size, one author is left without a partner.
+/// An empty roster stands for all authors that committed or co-authored a commit.
+/// Different authors with the same name, e.g. with different emails, are all on the roster.
+///
+/// The plan minimizes the sum of the [Suggestion::score] of all pairs, which prefers authors
+/// that have not paired at all, or not recently if a half-life is given.
+pub(crate) fn plan<'a>(
+    counts: &HashMap<usize, PairingCounts>,
+    string_cache: &'a StringCache,
+    roster: &[&'a str],
+    scoring: Scoring,
+) -> Vec<Suggestion<'a>> {
+    let authors = counts
+        .iter()
+        .filter(|(_, counts)| counts.commits() > 0)
+        .map(|(&id, _)| Author {
+            id: Some(id),
+            name: &string_cache[id],
+        })
+        .sorted_by_key(|author| (author.name, author.id))
+        .collect_vec();
+    let roster = if roster.is_empty() {
+        authors
+    } else {
+        roster
+            .iter()
+            .copied()
+            .sorted()
+            .dedup()
+            .flat_map(|name| {
+                let with_name = authors
+                    .iter()
+                    .copied()
+                    .filter(|author| author.name == name)
+                    .collect_vec();
+                if with_name.is_empty() {
+                    vec![Author { id: None, name }]
+                } else {
+                    with_name
+                }
+            })
+            .collect_vec()
+    };
+    let history = History::new(counts, &roster);
+    let score = |author: usize, co_author: Option<usize>| {
+        history
+            .suggestion(&roster, author, co_author, scoring)
+            .score
+    };
+
+    let pairs = if roster.len() <= MAX_EXACT_ROSTER {
+        exact(roster.len(), score)
+    } else {
+        greedy(roster.len(), score)
+    };
+    pairs
+        .into_iter()
+        .map(|(author, co_author)| history.suggestion(&roster, author, co_author, scoring))
+        .sorted_by(|lhs, rhs| {
+            (lhs.co_author.is_none(), lhs.author).cmp(&(rhs.co_author.is_none(), rhs.author))
+        })
+        .collect()
+}
+
+/// Finds the pairs with the lowest total score by trying all of them, memoizing by the set
+/// of authors that are already paired.
+fn exact(len: usize, score: impl Fn(usize, Option<usize>) -> f64) -> Vec<(usize, Option<usize>)> {
+    // an odd roster gets a placeholder partner for the author without a real one
+    let slots = len + len % 2;
+    let full = (1_usize << slots) - 1;
+    let partner = |slot: usize| Some(slot).filter(|&slot| slot < len);
+
+    let mut best = vec![f64::INFINITY; full + 1];
+    let mut choice = vec![0; full + 1];
+    best[full] = 0.0;
+    for paired in (0..full).rev() {
+        let first = (!paired).trailing_zeros() as usize;
+        if first >= len {
+            // only the placeholder is left, which never comes first
+            continue;
+        }
+        for second in first + 1..slots {
+            if paired & (1 << second) != 0 {
+                continue;
+            }
+            let rest = paired | (1 << first) | (1 << second);
+            let total = score(first, partner(second)) + best[rest];
+            if total < best[paired] {
+                best[paired] = total;
+                choice[paired] = second;
+            }
+        }
+    }
+
+    let mut pairs = Vec::with_capacity(slots / 2);
+    let mut paired = 0;
+    while paired != full {
+        let first = (!paired).trailing_zeros() as usize;
+        let second = choice[paired];
+        pairs.push((first, partner(second)));
+        paired |= (1 << first) | (1 << second);
+    }
+    pairs
+}
+
+/// Pairs the authors with the lowest score first, which is fast but not always the best plan.
+fn greedy(len: usize, score: impl Fn(usize, Option<usize>) -> f64) -> Vec<(usize, Option<usize>)> {
+    let candidates = (0..len)
+        .tuple_combinations()
+        .map(|(author, co_author)| (score(author, Some(co_author)), author, co_author))
+        .sorted_by(|lhs, rhs| lhs.0.total_cmp(&rhs.0))
+        .collect_vec();
+
+    let mut paired = vec![false; len];
+    let mut pairs = Vec::with_capacity(len / 2 + 1);
+    for (_, author, co_author) in candidates {
+        if !paired[author] && !paired[co_author] {
+            paired[author] = true;
+            paired[co_author] = true;
+            pairs.push((author, Some(co_author)));
+        }
+    }
+    pairs.extend(
+        (0..len)
+            .filter(|&author| !paired[author])
+            .map(|author| (author, None)),
+    );
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuthorCounts;
+    use git_stats_core::DiffStats;
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    fn counts_for(string_cache: &mut StringCache) -> AuthorCounts {
+        let [alice, bob, charlie, dave] =
+            ["Alice", "Bob", "Charlie", "Dave"].map(|name| string_cache.intern(name));
+
+        let mut counts = AuthorCounts::default();
+        let mut add = |time: i64, driver: usize, navigator: usize| {
+            counts.add_commit(driver, &[navigator], DiffStats::default());
+            counts.add_last_paired(time, driver, &[navigator]);
+        };
+        // Alice and Bob paired a lot, but long ago
+        for _ in 0..4 {
+            add(0, alice, bob);
+        }
+        add(0, charlie, dave);
+        // everyone else paired only once or twice, but recently
+        add(99 * DAY, alice, charlie);
+        add(99 * DAY, bob, dave);
+        add(98 * DAY, alice, dave);
+        add(98 * DAY, dave, alice);
+        add(98 * DAY, bob, charlie);
+        counts
+    }
+
+    fn pairs(suggestions: &[Suggestion<'_>]) -> Vec<(String, Option<String>)> {
+        suggestions
+            .iter()
+            .map(|s| (s.author.to_string(), s.co_author.map(str::to_string)))
+            .collect()
+    }
+
+    fn scoring(half_life: Option<f64>) -> Scoring {
+        Scoring {
+            weight: Weight::Commits,
+            half_life,
+            now: 100 * DAY,
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        let mut string_cache = StringCache::new();
+        let counts = counts_for(&mut string_cache).into_iter().collect();
+        let roster = ["Dave", "Charlie", "Bob", "Alice"];
+
+        let suggestions = plan(&counts, &string_cache, &roster, scoring(None));
+        assert_eq!(
+            pairs(&suggestions),
+            [
+                ("Alice".into(), Some("Charlie".into())),
+                ("Bob".into(), Some("Dave".into())),
+            ]
+        );
+        assert_eq!(suggestions[0].together, 1);
+        assert_eq!(suggestions[0].last_paired, Some(99 * DAY));
+    }
+
+    #[test]
+    fn test_plan_by_recency() {
+        let mut string_cache = StringCache::new();
+        let counts = counts_for(&mut string_cache).into_iter().collect();
+        let roster = ["Alice", "Bob", "Charlie", "Dave"];
+
+        let suggestions = plan(&counts, &string_cache, &roster, scoring(Some(7.0)));
+        assert_eq!(
+            pairs(&suggestions),
+            [
+                ("Alice".into(), Some("Bob".into())),
+                ("Charlie".into(), Some("Dave".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_odd_roster() {
+        let mut string_cache = StringCache::new();
+        let counts = counts_for(&mut string_cache).into_iter().collect();
+        let roster = ["Alice", "Bob", "Charlie", "Dave", "Eve"];
+
+        let suggestions = plan(&counts, &string_cache, &roster, scoring(None));
+        assert_eq!(suggestions.len(), 3);
+        // Eve has not paired with anyone yet, so they are not the one left without a partner
+        assert!(suggestions[..2]
+            .iter()
+            .any(|s| s.co_author == Some("Eve") || s.author == "Eve"));
+        assert_eq!(suggestions[2].co_author, None);
+        assert_eq!(suggestions.iter().map(|s| s.score).sum::<f64>(), 1.0);
+    }
+
+    #[test]
+    fn test_plan_same_name() {
+        let mut string_cache = StringCache::new();
+        let alex = string_cache.intern_with_label("alex@example.com", "Alex");
+        let other_alex = string_cache.intern_with_label("alex@example.org", "Alex");
+        let bob = string_cache.intern_with_label("bob@example.com", "Bob");
+        let charlie = string_cache.intern_with_label("charlie@example.com", "Charlie");
+
+        let mut counts = AuthorCounts::default();
+        counts.add_commit(alex, &[bob], DiffStats::default());
+        counts.add_commit(other_alex, &[charlie], DiffStats::default());
+        let counts = counts.into_iter().collect();
+
+        for roster in [&[][..], &["Alex", "Bob", "Charlie"]] {
+            let suggestions = plan(&counts, &string_cache, roster, scoring(None));
+            // both are on the roster, and neither is paired with their last partner again
+            let names = suggestions
+                .iter()
+                .flat_map(|s| [Some(s.author), s.co_author])
+                .flatten()
+                .sorted()
+                .collect_vec();
+            assert_eq!(names, ["Alex", "Alex", "Bob", "Charlie"]);
+            assert_eq!(suggestions.iter().map(|s| s.score).sum::<f64>(), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_greedy_matches_exact() {
+        // the cheapest pairs do not overlap, so pairing them first is the best plan
+        let score = |author: usize, co_author: Option<usize>| match co_author {
+            Some(co_author) if author / 2 == co_author / 2 => 0.0,
+            Some(co_author) => (author + co_author) as f64,
+            None => 0.0,
+        };
+        assert_eq!(greedy(6, score), [(0, Some(1)), (2, Some(3)), (4, Some(5))]);
+        assert_eq!(greedy(6, score), exact(6, score));
+    }
+
+    #[test]
+    fn test_exact_not_worse_than_greedy() {
+        let score = |author: usize, co_author: Option<usize>| match co_author {
+            Some(co_author) => ((author * 7 + co_author * 3) % 5) as f64,
+            None => 0.0,
+        };
+        let total = |pairs: Vec<(usize, Option<usize>)>| {
+            pairs
+                .into_iter()
+                .map(|(author, co_author)| score(author, co_author))
+                .sum::<f64>()
+        };
+        assert!(total(exact(6, score)) <= total(greedy(6, score)));
+        assert_eq!(exact(6, score).len(), 3);
+        assert_eq!(greedy(7, score).len(), 4);
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{AuthorCounts, PairingCounts, Repo, Result, StringCache, HAN_SOLO};
+use crate::{
+    Activity, AuthorCounts, Bucket, PairingCounts, Relationship, Repo, Result, Roster, ScanOptions,
+    StringCache, Weight,
+};
 use cursive::{
     theme::{ColorStyle, PaletteColor},
     View,
@@ -6,16 +9,39 @@ use cursive::{
 use itertools::Itertools;
 use std::rc::Rc;
 
+/// What is shown for the selected committer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Graph {
+    /// The commits with each co-author in the given relationship
+    Relationship(Relationship),
+    /// The commits by the number of people who worked on them
+    MobSizes,
+    /// The solo and paired commits over time
+    Timeline(Bucket),
+}
+
+impl Default for Graph {
+    fn default() -> Self {
+        Graph::Relationship(Relationship::default())
+    }
+}
+
 pub(crate) struct AuthorCountsView {
     current_counts: Option<Rc<PairingCounts>>,
     repo: Repo,
+    options: ScanOptions,
+    graph: Graph,
+    weight: Weight,
 }
 
 impl AuthorCountsView {
-    pub(crate) fn new(repo: Repo) -> AuthorCountsView {
+    pub(crate) fn new(repo: Repo, options: ScanOptions, weight: Weight) -> AuthorCountsView {
         AuthorCountsView {
             current_counts: Default::default(),
             repo,
+            options,
+            graph: Graph::default(),
+            weight,
         }
     }
 
@@ -23,17 +49,141 @@ impl AuthorCountsView {
         self.repo.string_cache()
     }
 
+    pub(crate) fn roster(&self) -> &Roster {
+        self.repo.roster()
+    }
+
     pub(crate) fn set_current_counts(&mut self, counts: Rc<PairingCounts>) {
         let _ = self.current_counts.replace(counts);
     }
 
-    pub(crate) fn counts_for_range(&mut self, range: Option<String>) -> Result<AuthorCounts> {
-        self.repo.extract_coauthors(range)
+    pub(crate) fn counts(&mut self) -> Result<AuthorCounts> {
+        Ok(self.repo.extract_coauthors(&self.options)?)
+    }
+
+    pub(crate) fn counts_for_filter(
+        &mut self,
+        range: Option<String>,
+        since: Option<i64>,
+        until: Option<i64>,
+    ) -> Result<AuthorCounts> {
+        self.options.range = range;
+        self.options.since = since;
+        self.options.until = until;
+        self.counts()
+    }
+
+    /// Counts again after changing the options, e.g. to compute what only some graphs need.
+    pub(crate) fn recount(
+        &mut self,
+        change: impl FnOnce(&mut ScanOptions),
+    ) -> Result<AuthorCounts> {
+        change(&mut self.options);
+        self.counts()
+    }
+
+    pub(crate) fn options(&self) -> &ScanOptions {
+        &self.options
+    }
+
+    pub(crate) fn graph(&self) -> Graph {
+        self.graph
+    }
+
+    pub(crate) fn set_graph(&mut self, graph: Graph) {
+        self.graph = graph;
+    }
+
+    pub(crate) fn weight(&self) -> Weight {
+        self.weight
+    }
+
+    pub(crate) fn set_weight(&mut self, weight: Weight) {
+        self.weight = weight;
     }
 
     fn current_counts(&self) -> Option<&PairingCounts> {
         self.current_counts.as_deref()
     }
+
+    /// Draws a bar per period, stacking the paired commits below the solo commits.
+    /// Only the most recent periods are shown if there is not enough space for all of them.
+    fn draw_timeline(
+        &self,
+        printer: &cursive::Printer<'_, '_>,
+        counts: &PairingCounts,
+        bucket: Bucket,
+    ) {
+        // fill the gaps, so that the distance between the bars is proportional to the time
+        let mut periods = Vec::<(i64, Activity)>::new();
+        for (start, activity) in counts.timeline() {
+            while let Some(&(last, _)) = periods.last() {
+                let next = bucket.next(last);
+                if next >= start {
+                    break;
+                }
+                periods.push((next, Activity::default()));
+            }
+            periods.push((start, activity));
+        }
+
+        let max_x = printer.size.x;
+        let max_height = printer.size.y.saturating_sub(2);
+        if periods.is_empty() || max_x == 0 || max_height == 0 {
+            return;
+        }
+
+        let column = (max_x / periods.len()).clamp(1, 8);
+        let bar_width = column.saturating_sub(1).max(1);
+        let shown = periods.len().min(max_x / column);
+        let periods = &periods[periods.len() - shown..];
+
+        let max_total = periods
+            .iter()
+            .map(|(_, activity)| activity.solo + activity.paired)
+            .max()
+            .unwrap_or_default()
+            .max(1) as usize;
+        // rounded up, so that a single commit is always visible
+        let height = |count: u32| (count as usize * max_height).div_ceil(max_total);
+
+        let paired_color = ColorStyle::title_primary();
+        let solo_color = ColorStyle::title_secondary();
+        let name_color = ColorStyle::primary();
+        let bottom = max_height;
+
+        for (index, (_, activity)) in periods.iter().enumerate() {
+            let total = height(activity.solo + activity.paired);
+            let paired = height(activity.paired).min(total);
+            let x = index * column;
+            for x in x..x + bar_width {
+                printer.with_color(paired_color, |p| {
+                    p.print_vline((x, bottom + 1 - paired), paired, FULL);
+                });
+                printer.with_color(solo_color, |p| {
+                    p.print_vline((x, bottom + 1 - total), total - paired, FULL);
+                });
+            }
+        }
+
+        printer.with_color(paired_color, |p| p.print((0, 0), FULL));
+        printer.with_color(name_color, |p| p.print((2, 0), "paired"));
+        printer.with_color(solo_color, |p| p.print((10, 0), FULL));
+        printer.with_color(name_color, |p| {
+            p.print((12, 0), "solo");
+
+            let first = bucket.label(periods[0].0);
+            let last = bucket.label(periods[shown - 1].0);
+            p.print((0, bottom + 1), &first);
+            let last_x = (shown - 1) * column;
+            if shown > 1 && last_x > first.len() {
+                p.print(
+                    (last_x.min(max_x.saturating_sub(last.len())), bottom + 1),
+                    &last,
+                );
+            }
+        });
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -55,6 +205,13 @@ const EMPTY: &str = " ";
 
 const BAR_GAP: usize = 2;
 
+/// A bar of the graph, where the commits as author are highlighted within the total.
+struct Bar {
+    label: String,
+    as_author: u64,
+    total: u64,
+}
+
 impl View for AuthorCountsView {
     fn draw(&self, printer: &cursive::Printer<'_, '_>) {
         let counts = match self.current_counts() {
@@ -62,15 +219,42 @@ impl View for AuthorCountsView {
             None => return,
         };
 
+        let weight = self.weight;
+        let bars = match self.graph {
+            Graph::Timeline(bucket) => return self.draw_timeline(printer, counts, bucket),
+            Graph::Relationship(relationship) => counts
+                .resolving_iter(self.string_cache())
+                .filter(|(_, commits)| commits.total_of(relationship) > 0)
+                .sorted_by_key(|(co_author, _)| *co_author)
+                .map(|(co_author, commits)| Bar {
+                    label: co_author.to_string(),
+                    as_author: commits.weighted_as_author_of(relationship, weight),
+                    total: commits.weighted_total_of(relationship, weight),
+                })
+                .collect_vec(),
+            Graph::MobSizes => counts
+                .group_sizes()
+                .map(|(size, commits)| Bar {
+                    label: if size == 1 {
+                        "solo".to_string()
+                    } else {
+                        size.to_string()
+                    },
+                    as_author: 0,
+                    total: commits.into(),
+                })
+                .collect_vec(),
+        };
+
         // calculate bar width and gap
         // the width is guaranteed to be an even number
         // as we want to split the bar into 2
         let max_x = printer.size.x - printer.offset.x;
-        let data_points = counts.len().max(1);
+        let data_points = bars.len().max(1);
         let width_per_author = max_x / data_points;
         let mut bar_gap = BAR_GAP;
         let mut bar_width = width_per_author.saturating_sub(bar_gap).max(1);
-        if bar_width % 2 != 0 {
+        if !bar_width.is_multiple_of(2) {
             bar_width += 1;
             bar_gap -= 1;
         }
@@ -82,9 +266,14 @@ impl View for AuthorCountsView {
 
         // get to available height of the current screen segment
         let max_view_y = printer.size.y - printer.offset.y + 1;
-        let max_y = max_view_y.saturating_sub(1) as u32;
+        let max_y = max_view_y.saturating_sub(1) as u64;
 
-        let max_count = counts.max_value().max(1);
+        let max_count = bars
+            .iter()
+            .map(|bar| bar.total)
+            .max()
+            .unwrap_or_default()
+            .max(1);
 
         // colors
         let driver_bar_color = ColorStyle::title_primary();
@@ -94,7 +283,7 @@ impl View for AuthorCountsView {
         let name_color = ColorStyle::primary();
 
         let draw_author_bar_inner = |index: usize,
-                                     count: u32,
+                                     count: u64,
                                      color: ColorStyle,
                                      value_color: ColorStyle,
                                      bar_placement: BarPlacement|
@@ -150,22 +339,24 @@ impl View for AuthorCountsView {
             x
         };
 
-        let mut counts = counts.resolving_iter(self.string_cache()).collect_vec();
-        counts.sort_by_key(|(k, _)| if *k == HAN_SOLO { "~" } else { *k });
-
-        for (index, (co_author, commits)) in counts.into_iter().enumerate() {
-            let name_pos = if commits.as_driver == 0 {
+        for (index, bar) in bars.into_iter().enumerate() {
+            let Bar {
+                label,
+                as_author,
+                total,
+            } = bar;
+            let name_pos = if as_author == 0 {
                 draw_author_bar_inner(
                     index,
-                    commits.total,
+                    total,
                     all_bar_color,
                     all_value_color,
                     BarPlacement::Full,
                 )
-            } else if commits.as_driver == commits.total {
+            } else if as_author == total {
                 draw_author_bar_inner(
                     index,
-                    commits.as_driver,
+                    as_author,
                     driver_bar_color,
                     driver_value_color,
                     BarPlacement::Full,
@@ -173,14 +364,14 @@ impl View for AuthorCountsView {
             } else {
                 let _ = draw_author_bar_inner(
                     index,
-                    commits.as_driver,
+                    as_author,
                     driver_bar_color,
                     driver_value_color,
                     BarPlacement::Left,
                 );
                 draw_author_bar_inner(
                     index,
-                    commits.total,
+                    total,
                     all_bar_color,
                     all_value_color,
                     BarPlacement::Right,
@@ -190,7 +381,7 @@ impl View for AuthorCountsView {
             printer.with_color(name_color, |p| {
                 p.print(
                     (name_pos, max_view_y),
-                    &format!("{:^1$.1$}", co_author, bar_width),
+                    &format!("{:^1$.1$}", label, bar_width),
                 );
             });
         }
@@ -0,0 +1,359 @@
+use crate::{AuthorCounts, Relationship, StringCache, Weight};
+use cursive::{
+    direction::Direction,
+    event::{Event, EventResult, Key},
+    theme::ColorStyle,
+    view::CannotFocus,
+    Printer, Rect, Vec2, View,
+};
+use itertools::Itertools;
+use std::collections::HashMap;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Shades for the cells, from few to many commits together.
+const SHADES: [&str; 4] = ["░░", "▒▒", "▓▓", "██"];
+const NONE: &str = "··";
+const DIAGONAL: &str = "╲╲";
+const CELL_WIDTH: usize = 3;
+const MAX_NAME_WIDTH: usize = 20;
+
+/// The order of the rows and columns.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Sort {
+    Name,
+    /// Authors with the most commits with others first
+    Activity,
+    /// Authors that worked together are next to each other, so that teams show up as blocks
+    Groups,
+}
+
+impl Sort {
+    fn next(self) -> Self {
+        match self {
+            Sort::Name => Sort::Activity,
+            Sort::Activity => Sort::Groups,
+            Sort::Groups => Sort::Name,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Sort::Name => "name",
+            Sort::Activity => "activity",
+            Sort::Groups => "groups",
+        }
+    }
+}
+
+/// A heatmap of how much every author worked with every other author.
+pub(crate) struct MatrixView {
+    names: Vec<String>,
+    /// The commits of two authors together, by their index in `names`.
+    totals: Vec<Vec<u64>>,
+    /// The commits of two authors together where the first one was the author.
+    as_author: Vec<Vec<u64>>,
+    max_total: u64,
+    unit: &'static str,
+    /// The indices of the authors in the order of the rows and columns.
+    order: Vec<usize>,
+    sort: Sort,
+    row: usize,
+    column: usize,
+}
+
+impl MatrixView {
+    pub(crate) fn new(
+        counts: AuthorCounts,
+        string_cache: &StringCache,
+        relationship: Relationship,
+        weight: Weight,
+    ) -> Self {
+        let counts = counts.into_iter().collect_vec();
+        let index = counts
+            .iter()
+            .enumerate()
+            .map(|(index, (author, _))| (*author, index))
+            .collect::<HashMap<_, _>>();
+
+        let mut totals = vec![vec![0; counts.len()]; counts.len()];
+        let mut as_author = totals.clone();
+        for (row, (_, pairing_counts)) in counts.iter().enumerate() {
+            for (co_author, paired_with) in pairing_counts.iter() {
+                let column = index[&co_author];
+                totals[row][column] = paired_with.weighted_total_of(relationship, weight);
+                as_author[row][column] = paired_with.weighted_as_author_of(relationship, weight);
+            }
+        }
+
+        let unit = match (relationship, weight) {
+            (Relationship::Review, _) => "reviewed commits",
+            (Relationship::Pairing, Weight::Commits) => "paired commits",
+            (Relationship::Pairing, Weight::Lines) => "changed lines",
+            (Relationship::Pairing, Weight::Files) => "changed files",
+        };
+
+        let mut view = MatrixView {
+            names: counts
+                .iter()
+                .map(|(author, _)| string_cache[*author].to_string())
+                .collect(),
+            max_total: totals.iter().flatten().copied().max().unwrap_or_default(),
+            totals,
+            as_author,
+            unit,
+            order: (0..counts.len()).collect(),
+            sort: Sort::Name,
+            row: 0,
+            column: 0,
+        };
+        view.sort_by(Sort::Name);
+        view
+    }
+
+    fn sort_by(&mut self, sort: Sort) {
+        // keep the same pair of authors selected
+        let selected = (self.order.get(self.row), self.order.get(self.column));
+        let (row, column) = (selected.0.copied(), selected.1.copied());
+
+        let by_name = |lhs: &usize, rhs: &usize| self.names[*lhs].cmp(&self.names[*rhs]);
+        let activity = |author: usize| self.totals[author].iter().sum::<u64>();
+        self.order = match sort {
+            Sort::Name => (0..self.names.len()).sorted_by(by_name).collect(),
+            Sort::Activity => (0..self.names.len())
+                .sorted_by(|lhs, rhs| activity(*rhs).cmp(&activity(*lhs)).then(by_name(lhs, rhs)))
+                .collect(),
+            Sort::Groups => self.groups(),
+        };
+        self.sort = sort;
+
+        let order = &self.order;
+        let position = |author: Option<usize>| {
+            author
+                .and_then(|author| order.iter().position(|&a| a == author))
+                .unwrap_or_default()
+        };
+        let (row, column) = (position(row), position(column));
+        self.row = row;
+        self.column = column;
+    }
+
+    /// Orders the authors greedily, so that each author is followed by their most frequent
+    /// partner that is not placed yet. A new group starts with the most active remaining author.
+    fn groups(&self) -> Vec<usize> {
+        let activity = |author: usize| self.totals[author].iter().sum::<u64>();
+        let mut remaining = (0..self.names.len())
+            .sorted_by(|lhs, rhs| {
+                activity(*rhs)
+                    .cmp(&activity(*lhs))
+                    .then(self.names[*lhs].cmp(&self.names[*rhs]))
+            })
+            .collect_vec();
+
+        let mut order = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let next = order
+                .iter()
+                .rev()
+                .find_map(|&placed: &usize| {
+                    remaining
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, &author)| self.totals[placed][author] > 0)
+                        .max_by_key(|(position, &author)| {
+                            (self.totals[placed][author], std::cmp::Reverse(*position))
+                        })
+                        .map(|(position, _)| position)
+                })
+                .unwrap_or(0);
+            order.push(remaining.remove(next));
+        }
+        order
+    }
+
+    fn name_width(&self) -> usize {
+        self.names
+            .iter()
+            .map(|name| name.width())
+            .max()
+            .unwrap_or_default()
+            .min(MAX_NAME_WIDTH)
+    }
+
+    /// The width of the row labels, which are the row number followed by the name.
+    fn label_width(&self) -> usize {
+        self.number_width() + 1 + self.name_width() + 1
+    }
+
+    fn number_width(&self) -> usize {
+        self.names.len().to_string().len()
+    }
+
+    fn shade(&self, total: u64) -> &'static str {
+        if total == 0 {
+            return NONE;
+        }
+        let level = (total * SHADES.len() as u64).div_ceil(self.max_total.max(1));
+        SHADES[(level as usize).clamp(1, SHADES.len()) - 1]
+    }
+
+    fn status(&self) -> String {
+        let (row, column) = match (self.order.get(self.row), self.order.get(self.column)) {
+            (Some(&row), Some(&column)) => (row, column),
+            _ => return "No authors".to_string(),
+        };
+        let hint = format!("Sorted by {}, press s to sort", self.sort.name());
+        if row == column {
+            return format!("{}  ({})", self.names[row], hint);
+        }
+        format!(
+            "{} and {}: {} {}, {} with {} as author  ({})",
+            self.names[row],
+            self.names[column],
+            self.totals[row][column],
+            self.unit,
+            self.as_author[row][column],
+            self.names[row],
+            hint
+        )
+    }
+}
+
+impl View for MatrixView {
+    fn draw(&self, printer: &Printer<'_, '_>) {
+        let label_width = self.label_width();
+        let number_width = self.number_width();
+        let name_width = self.name_width();
+        let highlight = ColorStyle::highlight();
+        let selected = ColorStyle::title_primary();
+
+        for (position, _) in self.order.iter().enumerate() {
+            let x = label_width + position * CELL_WIDTH;
+            let style = if position == self.column {
+                selected
+            } else {
+                ColorStyle::primary()
+            };
+            printer.with_color(style, |p| {
+                p.print((x, 0), &format!("{:>2}", position + 1));
+            });
+        }
+
+        for (row, &author) in self.order.iter().enumerate() {
+            let y = row + 1;
+            let style = if row == self.row {
+                selected
+            } else {
+                ColorStyle::primary()
+            };
+            printer.with_color(style, |p| {
+                let name = truncate(&self.names[author], name_width);
+                p.print((0, y), &format!("{:>2$} {}", row + 1, name, number_width));
+            });
+
+            for (column, &co_author) in self.order.iter().enumerate() {
+                let x = label_width + column * CELL_WIDTH;
+                let cell = if author == co_author {
+                    DIAGONAL
+                } else {
+                    self.shade(self.totals[author][co_author])
+                };
+                let style = if (row, column) == (self.row, self.column) {
+                    highlight
+                } else {
+                    ColorStyle::secondary()
+                };
+                printer.with_color(style, |p| p.print((x, y), cell));
+            }
+        }
+
+        printer.print((0, self.order.len() + 2), &self.status());
+    }
+
+    fn required_size(&mut self, _constraint: Vec2) -> Vec2 {
+        let width = self.label_width() + self.order.len() * CELL_WIDTH;
+        Vec2::new(width.max(self.status().width()), self.order.len() + 3)
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        let last = self.order.len().saturating_sub(1);
+        match event {
+            Event::Key(Key::Up) => self.row = self.row.saturating_sub(1),
+            Event::Key(Key::Down) => self.row = (self.row + 1).min(last),
+            Event::Key(Key::Left) => self.column = self.column.saturating_sub(1),
+            Event::Key(Key::Right) => self.column = (self.column + 1).min(last),
+            Event::Char('s') => self.sort_by(self.sort.next()),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Consumed(None)
+    }
+
+    fn take_focus(&mut self, _source: Direction) -> Result<EventResult, CannotFocus> {
+        Ok(EventResult::Consumed(None))
+    }
+
+    fn important_area(&self, _view_size: Vec2) -> Rect {
+        let x = self.label_width() + self.column * CELL_WIDTH;
+        Rect::from_size((x, self.row + 1), (CELL_WIDTH, 1))
+    }
+}
+
+/// Cuts the name off at the given display width, wide characters like CJK take two columns.
+fn truncate(name: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (index, c) in name.char_indices() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            return &name[..index];
+        }
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git_stats_core::DiffStats;
+
+    #[test]
+    fn test_sort_by_groups() {
+        let mut string_cache = StringCache::new();
+        let [alice, bob, charlie, dave, eve] =
+            ["Alice", "Bob", "Charlie", "Dave", "Eve"].map(|name| string_cache.intern(name));
+
+        let mut counts = AuthorCounts::default();
+        for _ in 0..3 {
+            counts.add_commit(alice, &[dave], DiffStats::default());
+        }
+        counts.add_commit(bob, &[charlie], DiffStats::default());
+        counts.add_commit(eve, &[], DiffStats::default());
+
+        let mut view = MatrixView::new(
+            counts,
+            &string_cache,
+            Relationship::Pairing,
+            Weight::Commits,
+        );
+        fn names(view: &MatrixView) -> Vec<&str> {
+            view.order
+                .iter()
+                .map(|&author| view.names[author].as_str())
+                .collect()
+        }
+        assert_eq!(names(&view), ["Alice", "Bob", "Charlie", "Dave", "Eve"]);
+
+        view.row = 3;
+        view.sort_by(Sort::Groups);
+        assert_eq!(names(&view), ["Alice", "Dave", "Bob", "Charlie", "Eve"]);
+        // the same author stays selected
+        assert_eq!(view.row, 1);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("Alice", 3), "Ali");
+        assert_eq!(truncate("Alice", 5), "Alice");
+        assert_eq!(truncate("山田太郎", 5), "山田");
+        assert_eq!(truncate("山田太郎", 4).width(), 4);
+        assert_eq!(truncate("Bob 🦀🦀", 6), "Bob 🦀");
+    }
+}
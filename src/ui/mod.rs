@@ -1,5 +1,8 @@
-use crate::{PairingCounts, Repo, Result};
-use author_counts_view::AuthorCountsView;
+use crate::{
+    date, AuthorCounts, Bucket, PairingCounts, Relationship, Repo, Result, Roster, ScanOptions,
+    StringCache, Weight,
+};
+use author_counts_view::{AuthorCountsView, Graph};
 use cursive::{
     align::{HAlign, VAlign},
     event::Key,
@@ -8,12 +11,18 @@ use cursive::{
     views::{Dialog, DummyView, EditView, LinearLayout, SelectView, TextView},
     Cursive,
 };
+use matrix_view::MatrixView;
 use std::rc::Rc;
+use unicode_width::UnicodeWidthStr;
 
 mod author_counts_view;
+mod matrix_view;
+mod picker;
+
+pub(crate) use picker::pick_co_authors;
 
-pub(crate) fn render_coauthors(repo: Repo, range: Option<String>) -> Result<()> {
-    let mut counts_view = AuthorCountsView::new(repo);
+pub(crate) fn render_coauthors(repo: Repo, options: ScanOptions, weight: Weight) -> Result<()> {
+    let mut counts_view = AuthorCountsView::new(repo, options, weight);
 
     let mut select = SelectView::<Rc<PairingCounts>>::new()
         // Center the text horizontally
@@ -25,25 +34,56 @@ pub(crate) fn render_coauthors(repo: Repo, range: Option<String>) -> Result<()>
         .on_submit(show_co_authors);
 
     // add all authors
-    let counts = counts_view.counts_for_range(range)?;
-    let counts = counts
-        .into_resolving_iter(counts_view.string_cache())
-        .map(|(author, counts)| (author, Rc::new(counts)));
-    select.add_all(counts);
+    let counts = counts_view.counts()?;
+    select.add_all(committers(counts, counts_view.string_cache()));
 
     // sort by author names
     select.sort_by_label();
 
+    let team_menu = team_menu(counts_view.roster());
+    let committers_title = committers_title(counts_view.options());
+
     let mut siv = cursive::default();
 
     add_global_callbacks(&mut siv);
 
-    let _ = siv
-        .menubar()
+    let menubar = siv.menubar();
+    let _ = menubar
         .add_subtree(
             "Filter",
-            MenuTree::new().leaf("Commit range", show_range_dialog),
+            MenuTree::new().leaf("Commit range and dates", show_range_dialog),
         )
+        .add_subtree(
+            "Graph",
+            MenuTree::new()
+                .leaf("Pairing", |s| {
+                    show_graph(s, Graph::Relationship(Relationship::Pairing))
+                })
+                .leaf("Review", |s| {
+                    show_graph(s, Graph::Relationship(Relationship::Review))
+                })
+                .leaf("Mob sizes", |s| show_graph(s, Graph::MobSizes))
+                .subtree(
+                    "Timeline",
+                    MenuTree::new()
+                        .leaf("Days", |s| show_timeline(s, Bucket::Day))
+                        .leaf("Weeks", |s| show_timeline(s, Bucket::Week))
+                        .leaf("Months", |s| show_timeline(s, Bucket::Month))
+                        .leaf("Quarters", |s| show_timeline(s, Bucket::Quarter)),
+                ),
+        )
+        .add_subtree(
+            "Weight",
+            MenuTree::new()
+                .leaf("Commits", |s| show_weight(s, Weight::Commits))
+                .leaf("Lines", |s| show_weight(s, Weight::Lines))
+                .leaf("Files", |s| show_weight(s, Weight::Files)),
+        );
+    if let Some(team_menu) = team_menu {
+        let _ = menubar.add_subtree("Team", team_menu);
+    }
+    let _ = menubar
+        .add_leaf("Matrix", show_matrix)
         .add_delimiter()
         .add_leaf("Quit", Cursive::quit);
 
@@ -57,12 +97,14 @@ pub(crate) fn render_coauthors(repo: Repo, range: Option<String>) -> Result<()>
                 Dialog::around(
                     select.with_name("committers").scrollable().full_height(), // .fixed_width(usize::from(app.author_widget_width()))
                 )
-                .title("Committer"),
+                .title(committers_title)
+                .with_name("committers-dialog"),
             )
             .child(DummyView.fixed_width(1))
             .child(
                 Dialog::around(counts_view.with_name("co-authors").full_width()) // TextView::new("foobar").with_name("co-authors")
-                    .title("Co-authors"),
+                    .title(title(Graph::default(), weight))
+                    .with_name("co-authors-dialog"),
             )
             .full_screen(),
     );
@@ -72,6 +114,33 @@ pub(crate) fn render_coauthors(repo: Repo, range: Option<String>) -> Result<()>
     Ok(())
 }
 
+/// Labels each committer with the share of their commits that were paired, e.g. `Alice   71%`.
+fn committers(
+    counts: AuthorCounts,
+    string_cache: &StringCache,
+) -> Vec<(String, Rc<PairingCounts>)> {
+    let counts = counts.into_resolving_iter(string_cache).collect::<Vec<_>>();
+    let width = counts
+        .iter()
+        .map(|(author, _)| author.width())
+        .max()
+        .unwrap_or_default();
+
+    counts
+        .into_iter()
+        .map(|(author, counts)| {
+            let pairing = match counts.pairing_ratio() {
+                Some(ratio) => format!("{:>3.0}%", ratio * 100.0),
+                // the author only reviewed commits
+                None => "   -".to_string(),
+            };
+            let padding = " ".repeat(width - author.width());
+            let label = format!("{}{} {}", author, padding, pairing);
+            (label, Rc::new(counts))
+        })
+        .collect()
+}
+
 fn show_co_authors(siv: &mut Cursive, counts: &Rc<PairingCounts>) {
     siv.call_on_name("co-authors", |app: &mut AuthorCountsView| {
         app.set_current_counts(Rc::clone(counts));
@@ -79,6 +148,207 @@ fn show_co_authors(siv: &mut Cursive, counts: &Rc<PairingCounts>) {
     .unwrap();
 }
 
+fn show_graph(siv: &mut Cursive, graph: Graph) {
+    siv.call_on_name("co-authors", |app: &mut AuthorCountsView| {
+        app.set_graph(graph);
+    })
+    .unwrap();
+    update_title(siv);
+}
+
+fn show_weight(siv: &mut Cursive, weight: Weight) {
+    let has_diff_stats = siv
+        .call_on_name("co-authors", |app: &mut AuthorCountsView| {
+            app.options().diff_stats
+        })
+        .unwrap();
+    // the changes of the commits are only computed once they are needed, as that is slow
+    if weight != Weight::Commits
+        && !has_diff_stats
+        && !recount(siv, |options| options.diff_stats = true)
+    {
+        return;
+    }
+
+    siv.call_on_name("co-authors", |app: &mut AuthorCountsView| {
+        app.set_weight(weight);
+    })
+    .unwrap();
+    update_title(siv);
+}
+
+fn show_timeline(siv: &mut Cursive, bucket: Bucket) {
+    let current = siv
+        .call_on_name("co-authors", |app: &mut AuthorCountsView| {
+            app.options().bucket
+        })
+        .unwrap();
+    if current != Some(bucket) && !recount(siv, |options| options.bucket = Some(bucket)) {
+        return;
+    }
+
+    show_graph(siv, Graph::Timeline(bucket));
+}
+
+/// Counts again after changing the options and shows the new counts of the selected committer.
+/// Returns whether counting succeeded.
+fn recount(siv: &mut Cursive, change: impl FnOnce(&mut ScanOptions)) -> bool {
+    let mut app = siv.find_name::<AuthorCountsView>("co-authors").unwrap();
+    let counts = match app.recount(change) {
+        Ok(counts) => counts,
+        Err(err) => {
+            drop(app);
+            show_error(siv, err.to_string());
+            return false;
+        }
+    };
+
+    let selection = siv
+        .call_on_name(
+            "committers",
+            |select: &mut SelectView<Rc<PairingCounts>>| {
+                let selected = select.selected_id();
+                select.clear();
+                select.add_all(committers(counts, app.string_cache()));
+                select.sort_by_label();
+                let _ = select.set_selection(selected?);
+                select.selection()
+            },
+        )
+        .unwrap();
+    if let Some(counts) = selection {
+        app.set_current_counts(Rc::clone(&counts));
+    }
+    true
+}
+
+/// The filters by the roster, or none without a roster.
+fn team_menu(roster: &Roster) -> Option<MenuTree> {
+    if roster.is_empty() {
+        return None;
+    }
+
+    let mut menu = MenuTree::new()
+        .leaf("Everyone", |s| show_team(s, false, Vec::new()))
+        .leaf("Roster members", |s| show_team(s, true, Vec::new()))
+        .delimiter();
+    for team in roster.teams() {
+        let team = team.to_string();
+        menu.add_leaf(team.clone(), move |s| {
+            show_team(s, false, vec![team.clone()])
+        });
+    }
+    Some(menu)
+}
+
+fn show_team(siv: &mut Cursive, members_only: bool, teams: Vec<String>) {
+    let changed = recount(siv, |options| {
+        options.members_only = members_only;
+        options.teams = teams;
+    });
+    if !changed {
+        return;
+    }
+
+    let title = siv
+        .call_on_name("co-authors", |app: &mut AuthorCountsView| {
+            committers_title(app.options())
+        })
+        .unwrap();
+    siv.call_on_name("committers-dialog", |dialog: &mut Dialog| {
+        dialog.set_title(title);
+    })
+    .unwrap();
+}
+
+/// Names the teams the committers are filtered by, e.g. `Committer (platform)`.
+fn committers_title(options: &ScanOptions) -> String {
+    if !options.teams.is_empty() {
+        format!("Committer ({})", options.teams.join(", "))
+    } else if options.members_only {
+        "Committer (roster)".to_string()
+    } else {
+        "Committer".to_string()
+    }
+}
+
+fn update_title(siv: &mut Cursive) {
+    let title = siv
+        .call_on_name("co-authors", |app: &mut AuthorCountsView| {
+            title(app.graph(), app.weight())
+        })
+        .unwrap();
+    siv.call_on_name("co-authors-dialog", |dialog: &mut Dialog| {
+        dialog.set_title(title);
+    })
+    .unwrap();
+}
+
+fn title(graph: Graph, weight: Weight) -> String {
+    let title = match graph {
+        Graph::Relationship(Relationship::Pairing) => "Co-authors",
+        Graph::Relationship(Relationship::Review) => "Reviews",
+        Graph::MobSizes => "Mob sizes",
+        Graph::Timeline(Bucket::Day) => "Pairing per day",
+        Graph::Timeline(Bucket::Week) => "Pairing per week",
+        Graph::Timeline(Bucket::Month) => "Pairing per month",
+        Graph::Timeline(Bucket::Quarter) => "Pairing per quarter",
+    };
+    // reviews, mob sizes, and the timeline are always counted by commits
+    match (graph, weight) {
+        (Graph::Relationship(Relationship::Pairing), Weight::Lines) => {
+            format!("{} by lines", title)
+        }
+        (Graph::Relationship(Relationship::Pairing), Weight::Files) => {
+            format!("{} by files", title)
+        }
+        _ => title.to_string(),
+    }
+}
+
+/// Shows how much every author worked with every other author,
+/// in the relationship and with the weight of the current graph.
+fn show_matrix(siv: &mut Cursive) {
+    let mut app = siv.find_name::<AuthorCountsView>("co-authors").unwrap();
+    let relationship = match app.graph() {
+        Graph::Relationship(relationship) => relationship,
+        Graph::MobSizes | Graph::Timeline(_) => Relationship::Pairing,
+    };
+    let weight = app.weight();
+    let matrix = match app.counts() {
+        Ok(counts) => MatrixView::new(counts, app.string_cache(), relationship, weight),
+        Err(err) => {
+            drop(app);
+            show_error(siv, err.to_string());
+            return;
+        }
+    };
+    drop(app);
+
+    disable_menu_bar(siv);
+    siv.add_layer(
+        Dialog::around(matrix.scrollable().scroll_x(true))
+            .title(format!(
+                "{} matrix",
+                title(Graph::Relationship(relationship), weight)
+            ))
+            .button("Close", |s| {
+                let _ = s.pop_layer();
+                enable_menu_bar(s);
+            }),
+    );
+}
+
+fn show_error(siv: &mut Cursive, message: String) {
+    siv.add_layer(
+        Dialog::around(TextView::new(message))
+            .title("Error")
+            .button("Ok", |s| {
+                let _ = s.pop_layer();
+            }),
+    );
+}
+
 fn show_range_dialog(siv: &mut Cursive) {
     disable_menu_bar(siv);
 
@@ -89,27 +359,41 @@ fn show_range_dialog(siv: &mut Cursive) {
         let range_end = siv
             .call_on_name("range_end", |view: &mut EditView| view.get_content())
             .unwrap();
+        let since = siv
+            .call_on_name("since", |view: &mut EditView| view.get_content())
+            .unwrap();
+        let until = siv
+            .call_on_name("until", |view: &mut EditView| view.get_content())
+            .unwrap();
+        let separator = siv
+            .call_on_name("range_separator", |view: &mut TextView| {
+                view.get_content().source().to_string()
+            })
+            .unwrap();
+
+        // a single revision has no separator until an end is added
+        let separator = if separator.is_empty() && !range_end.is_empty() {
+            ".."
+        } else {
+            separator.as_str()
+        };
 
         // set to full range if nothing is specified
         let range = if range_start.is_empty() && range_end.is_empty() {
             None
         } else {
-            Some(format!("{}..{}", range_start, range_end))
+            Some(format!("{}{}{}", range_start, separator, range_end))
         };
 
         let mut app = siv.find_name::<AuthorCountsView>("co-authors").unwrap();
 
-        match app.counts_for_range(range) {
+        match counts_for_filter(&mut app, range, &since, &until) {
             Ok(counts) => {
                 siv.call_on_name(
                     "committers",
                     move |select: &mut SelectView<Rc<PairingCounts>>| {
-                        let counts = counts
-                            .into_resolving_iter(app.string_cache())
-                            .map(|(author, counts)| (author, Rc::new(counts)));
-
                         select.clear();
-                        select.add_all(counts);
+                        select.add_all(committers(counts, app.string_cache()));
                         select.sort_by_label();
                     },
                 )
@@ -118,31 +402,107 @@ fn show_range_dialog(siv: &mut Cursive) {
                 let _ = siv.pop_layer();
                 enable_menu_bar(siv)
             }
-            Err(err) => {
-                siv.add_layer(
-                    Dialog::around(TextView::new(err.to_string()))
-                        .title("Error")
-                        .button("Ok", |s| {
-                            let _ = s.pop_layer();
-                        }),
-                );
-            }
+            Err(err) => show_error(siv, err.to_string()),
         }
     }
 
+    let (range_start, separator, range_end, since, until) = siv
+        .call_on_name("co-authors", |app: &mut AuthorCountsView| {
+            let options = app.options();
+            let (range_start, separator, range_end) = options
+                .range
+                .as_deref()
+                .map(split_range)
+                .unwrap_or(("", "..", ""));
+            (
+                range_start.to_string(),
+                separator.to_string(),
+                range_end.to_string(),
+                options.since.map(date::format).unwrap_or_default(),
+                options.until.map(date::format).unwrap_or_default(),
+            )
+        })
+        .unwrap();
+
     siv.add_layer(
         Dialog::around(
-            LinearLayout::horizontal()
-                .child(EditView::new().with_name("range_start").fixed_width(20))
-                .child(TextView::new(".."))
-                .child(EditView::new().with_name("range_end").fixed_width(20)),
+            LinearLayout::vertical()
+                .child(
+                    LinearLayout::horizontal()
+                        .child(TextView::new("Range ").fixed_width(7))
+                        .child(
+                            EditView::new()
+                                .content(range_start)
+                                .with_name("range_start")
+                                .fixed_width(20),
+                        )
+                        .child(TextView::new(separator).with_name("range_separator"))
+                        .child(
+                            EditView::new()
+                                .content(range_end)
+                                .with_name("range_end")
+                                .fixed_width(20),
+                        ),
+                )
+                .child(DummyView)
+                .child(
+                    LinearLayout::horizontal()
+                        .child(TextView::new("Since ").fixed_width(7))
+                        .child(
+                            EditView::new()
+                                .content(since)
+                                .with_name("since")
+                                .fixed_width(42),
+                        ),
+                )
+                .child(
+                    LinearLayout::horizontal()
+                        .child(TextView::new("Until ").fixed_width(7))
+                        .child(
+                            EditView::new()
+                                .content(until)
+                                .with_name("until")
+                                .fixed_width(42),
+                        ),
+                ),
         )
-        .title("Enter commit range")
+        .title("Enter commit range and dates")
         .button("Ok", ok)
         .with_name("range_dialog"),
     );
 }
 
+/// Splits a range like `main..topic` or `main...topic` into its start, separator, and end.
+/// A single revision like `main` is the start, without a separator.
+fn split_range(range: &str) -> (&str, &str, &str) {
+    // the symmetric difference has to be tried first, as it contains the other separator
+    ["...", ".."]
+        .iter()
+        .find_map(|separator| {
+            let (start, end) = range.split_once(separator)?;
+            Some((start, *separator, end))
+        })
+        .unwrap_or((range, "", ""))
+}
+
+/// Applies the filter from the range dialog. Empty dates mean that commits are not filtered by date.
+fn counts_for_filter(
+    app: &mut AuthorCountsView,
+    range: Option<String>,
+    since: &str,
+    until: &str,
+) -> Result<AuthorCounts> {
+    let since = Some(since.trim())
+        .filter(|since| !since.is_empty())
+        .map(date::parse_since)
+        .transpose()?;
+    let until = Some(until.trim())
+        .filter(|until| !until.is_empty())
+        .map(date::parse_until)
+        .transpose()?;
+    app.counts_for_filter(range, since, until)
+}
+
 fn add_global_callbacks(siv: &mut Cursive) {
     enable_menu_bar(siv);
 
@@ -162,3 +522,21 @@ fn enable_menu_bar(siv: &mut Cursive) {
 fn disable_menu_bar(siv: &mut Cursive) {
     siv.set_global_callback(Key::Esc, |_s| ());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_range() {
+        assert_eq!(split_range("main..topic"), ("main", "..", "topic"));
+        assert_eq!(split_range("main...topic"), ("main", "...", "topic"));
+        assert_eq!(split_range("v1.0..."), ("v1.0", "...", ""));
+    }
+
+    #[test]
+    fn test_split_single_revision() {
+        assert_eq!(split_range("main"), ("main", "", ""));
+        assert_eq!(split_range("v1.0"), ("v1.0", "", ""));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{KnownAuthor, Result};
+use cursive::{
+    event::Key,
+    traits::{Nameable, Resizable, Scrollable},
+    views::{Dialog, EditView, LinearLayout, SelectView, TextView},
+    Cursive,
+};
+use itertools::Itertools;
+
+/// The authors that can be picked and the indices of the ones that were picked.
+struct Picker {
+    authors: Vec<KnownAuthor>,
+    picked: Vec<usize>,
+    confirmed: bool,
+}
+
+/// Lets the user pick co-authors with a fuzzy search and returns them in the order they were picked.
+pub(crate) fn pick_co_authors(authors: Vec<KnownAuthor>) -> Result<Vec<KnownAuthor>> {
+    let mut siv = cursive::default();
+    siv.set_user_data(Picker {
+        authors,
+        picked: Vec::new(),
+        confirmed: false,
+    });
+
+    let candidates = SelectView::<usize>::new()
+        .on_submit(|s, &index| toggle(s, index))
+        .with_name("candidates")
+        .scrollable()
+        .min_height(10);
+
+    siv.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(
+                    EditView::new()
+                        .on_edit(|s, query, _| refresh(s, query))
+                        .on_submit(pick_first)
+                        .with_name("query")
+                        .min_width(50),
+                )
+                .child(candidates)
+                .child(TextView::new("").with_name("picked")),
+        )
+        .title("Type to search, Enter to pick a co-author")
+        .button("Done", |s| {
+            s.with_user_data(|picker: &mut Picker| picker.confirmed = true);
+            s.quit();
+        })
+        .button("Cancel", Cursive::quit),
+    );
+    siv.add_global_callback(Key::Esc, Cursive::quit);
+
+    refresh(&mut siv, "");
+    siv.run();
+
+    let picker = siv.take_user_data::<Picker>().unwrap();
+    if !picker.confirmed {
+        return Ok(Vec::new());
+    }
+    let mut authors = picker.authors.into_iter().map(Some).collect_vec();
+    Ok(picker
+        .picked
+        .into_iter()
+        .filter_map(|index| authors[index].take())
+        .collect())
+}
+
+/// Picks the best match for the query, then clears the query for the next search.
+fn pick_first(siv: &mut Cursive, _query: &str) {
+    let first = siv
+        .call_on_name("candidates", |view: &mut SelectView<usize>| {
+            view.get_item(0).map(|(_, &index)| index)
+        })
+        .flatten();
+    if let Some(index) = first {
+        toggle(siv, index);
+        siv.call_on_name("query", |view: &mut EditView| view.set_content(""));
+        refresh(siv, "");
+    }
+}
+
+fn toggle(siv: &mut Cursive, index: usize) {
+    siv.with_user_data(|picker: &mut Picker| {
+        match picker.picked.iter().position(|&picked| picked == index) {
+            Some(position) => {
+                let _ = picker.picked.remove(position);
+            }
+            None => picker.picked.push(index),
+        }
+    });
+    let query = siv
+        .call_on_name("query", |view: &mut EditView| view.get_content())
+        .unwrap();
+    refresh(siv, &query);
+}
+
+/// Shows the authors that match the query, best matches first, as well as the picked authors.
+fn refresh(siv: &mut Cursive, query: &str) {
+    let (candidates, picked) = siv
+        .with_user_data(|picker: &mut Picker| {
+            let candidates = picker
+                .authors
+                .iter()
+                .enumerate()
+                .filter_map(|(index, author)| {
+                    let label = label(author);
+                    let score = fuzzy_score(query, &label)?;
+                    let mark = if picker.picked.contains(&index) {
+                        "[x]"
+                    } else {
+                        "[ ]"
+                    };
+                    Some((score, format!("{} {}", mark, label), index))
+                })
+                .sorted_by(|lhs, rhs| lhs.0.cmp(&rhs.0))
+                .map(|(_, label, index)| (label, index))
+                .collect_vec();
+            let picked = picker
+                .picked
+                .iter()
+                .map(|&index| picker.authors[index].name.as_str())
+                .join(", ");
+            (candidates, picked)
+        })
+        .unwrap();
+
+    siv.call_on_name("candidates", |view: &mut SelectView<usize>| {
+        view.clear();
+        view.add_all(candidates);
+    });
+    siv.call_on_name("picked", |view: &mut TextView| {
+        view.set_content(format!("Co-authors: {}", picked));
+    });
+}
+
+fn label(author: &KnownAuthor) -> String {
+    match &author.mail {
+        Some(mail) => format!("{} <{}>", author.name, mail),
+        None => author.name.clone(),
+    }
+}
+
+/// Scores how well the query matches the candidate as a case insensitive subsequence, lower is better.
+///
+/// Each character of the query that does not directly follow the previous match adds the
+/// number of skipped characters, so that `ali` prefers `Alice` over `Anna Lisa`.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<usize> {
+    let mut candidate = candidate.chars().flat_map(char::to_lowercase).enumerate();
+    let mut score = 0;
+    let mut next_position = 0;
+    for query_char in query.chars().flat_map(char::to_lowercase) {
+        if query_char.is_whitespace() {
+            continue;
+        }
+        let (position, _) = candidate.find(|(_, c)| *c == query_char)?;
+        score += position - next_position;
+        next_position = position + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Alice"), Some(0));
+        assert_eq!(fuzzy_score("ali", "Alice"), Some(0));
+        assert_eq!(fuzzy_score("ALI", "alice"), Some(0));
+        assert_eq!(fuzzy_score("ali", "Anna Lisa"), Some(4));
+        assert_eq!(fuzzy_score("jm", "Jörg Müller"), Some(4));
+        assert_eq!(fuzzy_score("bob", "Alice"), None);
+    }
+}